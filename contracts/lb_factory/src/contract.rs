@@ -3,9 +3,13 @@ use cosmwasm_std::{
     entry_point, to_binary, Binary, ContractInfo, Deps, DepsMut, Env, MessageInfo, Reply, Response,
     SubMsgResult, Uint128,
 };
-use liquidity_book::interfaces::lb_factory::*;
-use secret_toolkit::crypto::sha_256;
-use std::sync::LazyLock;
+use liquidity_book::{
+    core::{
+        access_control::{has_role, owner, pending_owner, set_owner},
+        contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+    },
+    interfaces::lb_factory::*,
+};
 
 mod execute;
 mod helper;
@@ -16,17 +20,18 @@ use execute::*;
 use query::*;
 use state::*;
 
-// TODO: Figure out the role stuff from original.
-// I need to learn about Ownable2Step and AccessControl from solidity.
-
-// TODO: see if LazyLock works OK in contracts
-// using sha256 instead of keccak256 just because it's more easily available
-pub static LB_HOOKS_MANAGER_ROLE: LazyLock<[u8; 32]> =
-    LazyLock::new(|| sha_256(b"LB_HOOKS_MANAGER_ROLE"));
 static OFFSET_IS_PRESET_OPEN: u8 = 255;
 static MIN_BIN_STEP: u8 = 1; // 0.001%
 static MAX_FLASH_LOAN_FEE: Uint128 = Uint128::new(10_u128.pow(17)); // 10%
 static PUBLIC_VIEWING_KEY: &str = "lb_rocks"; // TODO: decide if this should be public and static
+static DEFAULT_LB_PAIRS_LIMIT: u32 = 10;
+static MAX_LB_PAIRS_LIMIT: u32 = 30;
+static MAX_TIMELOCK_DELAY: u64 = 30 * 24 * 60 * 60; // 30 days
+static DEFAULT_PENDING_OPERATIONS_LIMIT: u32 = 10;
+static MAX_PENDING_OPERATIONS_LIMIT: u32 = 30;
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const CREATE_LB_PAIR_REPLY_ID: u64 = 1u64;
 
@@ -42,19 +47,19 @@ pub fn instantiate(
             address: env.contract.address,
             code_hash: env.contract.code_hash,
         },
-        owner: msg.owner.unwrap_or_else(|| info.sender.clone()),
-        admin_auth: msg.admin_auth.validate(deps.api)?,
-        query_auth: msg.query_auth.validate(deps.api)?,
     };
 
     STATE.save(deps.storage, &config)?;
+    let event = set_owner(deps.storage, &msg.owner.unwrap_or(info.sender))?;
     CONTRACT_STATUS.save(deps.storage, &ContractStatus::Active)?;
 
     FEE_RECIPIENT.save(deps.storage, &msg.fee_recipient)?;
     LB_PAIR_IMPLEMENTATION.save(deps.storage, &Implementation::empty())?;
     LB_TOKEN_IMPLEMENTATION.save(deps.storage, &Implementation::empty())?;
 
-    Ok(Response::default())
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default().add_event(event))
 }
 
 #[entry_point]
@@ -182,6 +187,12 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
             token_y,
             bin_step,
         } => remove_lb_hooks_on_pair(deps, env, info, token_x, token_y, bin_step),
+        ExecuteMsg::AddHooksToWhitelist {
+            hooks_implementation,
+        } => add_hooks_to_whitelist(deps, env, info, hooks_implementation),
+        ExecuteMsg::RemoveHooksFromWhitelist { code_id } => {
+            remove_hooks_from_whitelist(deps, env, info, code_id)
+        }
         ExecuteMsg::SetFeeRecipient { fee_recipient } => {
             set_fee_recipient(deps, env, info, fee_recipient)
         }
@@ -191,6 +202,38 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         ExecuteMsg::AddQuoteAsset { asset } => add_quote_asset(deps, env, info, asset),
         ExecuteMsg::RemoveQuoteAsset { asset } => remove_quote_asset(deps, env, info, asset),
         ExecuteMsg::ForceDecay { pair } => force_decay(deps, env, info, pair),
+        ExecuteMsg::SetPausedOperationsOnPair {
+            token_x,
+            token_y,
+            bin_step,
+            operations,
+            paused,
+        } => set_paused_operations_on_pair(
+            deps, env, info, token_x, token_y, bin_step, operations, paused,
+        ),
+        ExecuteMsg::SetStatusOnAllPairs {
+            status,
+            start_after,
+            limit,
+        } => set_status_on_all_pairs(deps, env, info, status, start_after, limit),
+        ExecuteMsg::MigrateLbPairs { start_after, limit } => {
+            migrate_lb_pairs(deps, env, info, start_after, limit)
+        }
+        ExecuteMsg::GrantRole { role, account } => grant_lb_role(deps, env, info, role, account),
+        ExecuteMsg::RevokeRole { role, account } => revoke_lb_role(deps, env, info, role, account),
+        ExecuteMsg::RenounceRole { role } => renounce_lb_role(deps, env, info, role),
+        ExecuteMsg::SetTimelockDelay { delay } => set_timelock_delay(deps, env, info, delay),
+        ExecuteMsg::ExecuteTimelockedOperation { id } => {
+            execute_timelocked_operation(deps, env, info, id)
+        }
+        ExecuteMsg::CancelTimelockedOperation { id } => {
+            cancel_timelocked_operation(deps, env, info, id)
+        }
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            transfer_factory_ownership(deps, env, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => accept_factory_ownership(deps, env, info),
+        ExecuteMsg::RenounceOwnership {} => renounce_factory_ownership(deps, env, info),
     }
 }
 
@@ -221,10 +264,47 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary> {
         QueryMsg::GetAllLbPairs { token_x, token_y } => {
             to_binary(&get_all_lb_pairs(deps, token_x, token_y)?)
         }
+        QueryMsg::GetHooksWhitelist {} => to_binary(&get_hooks_whitelist(deps)?),
+        QueryMsg::GetPairsStatus { start_after, limit } => {
+            to_binary(&get_pairs_status(deps, start_after, limit)?)
+        }
+        QueryMsg::GetTimelockDelay {} => to_binary(&get_timelock_delay(deps)?),
+        QueryMsg::GetPendingOperations { start_after, limit } => {
+            to_binary(&get_pending_operations(deps, start_after, limit)?)
+        }
+        QueryMsg::GetOwner {} => to_binary(&OwnerResponse {
+            owner: owner(deps.storage)?,
+        }),
+        QueryMsg::GetPendingOwner {} => to_binary(&PendingOwnerResponse {
+            pending_owner: pending_owner(deps.storage)?,
+        }),
+        QueryMsg::HasRole { role, account } => to_binary(&HasRoleResponse {
+            has_role: has_role(deps.storage, &role, &deps.api.addr_validate(&account)?)?,
+        }),
     }
     .map_err(Error::StdError)
 }
 
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Factories instantiated with the external admin-auth contract stored their owner in the state.
+    if previous.is_none() {
+        let legacy_state = LEGACY_STATE.load(deps.storage)?;
+
+        STATE.save(
+            deps.storage,
+            &State {
+                contract_info: legacy_state.contract_info,
+            },
+        )?;
+        set_owner(deps.storage, &legacy_state.owner)?;
+    }
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
 #[entry_point]
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response> {
     match (msg.id, msg.result) {
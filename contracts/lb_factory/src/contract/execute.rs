@@ -1,19 +1,27 @@
 use super::{
     helper::{_get_lb_pair_information, _is_preset_open, _sort_tokens},
     state::*,
-    CREATE_LB_PAIR_REPLY_ID, MIN_BIN_STEP, OFFSET_IS_PRESET_OPEN,
+    CREATE_LB_PAIR_REPLY_ID, DEFAULT_LB_PAIRS_LIMIT, MAX_LB_PAIRS_LIMIT, MAX_TIMELOCK_DELAY,
+    MIN_BIN_STEP, OFFSET_IS_PRESET_OPEN,
 };
 use crate::{contract::MAX_FLASH_LOAN_FEE, Error, Result};
 use cosmwasm_std::{
-    to_binary, Addr, Binary, ContractInfo, CosmosMsg, DepsMut, Env, Event, MessageInfo, Response,
-    SubMsg, Uint128, WasmMsg,
+    to_binary, Addr, Binary, ContractInfo, ContractInfoResponse, CosmosMsg, Deps, DepsMut, Env,
+    Event, MessageInfo, QueryRequest, Response, Storage, SubMsg, Uint128, WasmMsg, WasmQuery,
 };
 use liquidity_book::{
-    core::{RawContract, TokenType},
+    core::{
+        access_control::{
+            accept_ownership, grant_role, only_owner, only_role, owner, renounce_ownership,
+            renounce_role, revoke_role, transfer_ownership, LB_HOOKS_MANAGER_ROLE,
+        },
+        RawContract, TokenType,
+    },
     interfaces::{
         lb_factory::*,
         lb_pair::{
-            ExecuteMsg as LbPairExecuteMsg, ILbPair, InstantiateMsg as LbPairInstantiateMsg, LbPair,
+            ContractStatus as LbPairContractStatus, ExecuteMsg as LbPairExecuteMsg, ILbPair,
+            InstantiateMsg as LbPairInstantiateMsg, LbPair,
         },
     },
     libraries::{
@@ -24,25 +32,33 @@ use liquidity_book::{
 // use secret_toolkit::utils::HandleCallback as ExecuteCallback;
 // NOTE: I decided to copy this from shade because I think I will need it for multi-test
 use liquidity_book::core::callback::ExecuteCallback;
-use shade_protocol::admin::helpers::{validate_admin, AdminPermissions};
 
 /// Set the LbPair implementation details.
 ///
 /// Needs to be called by the owner.
 pub fn set_lb_pair_implementation(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     new_lb_pair_implementation: Implementation,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
+
+    let operation = TimelockedOperation::SetLbPairImplementation {
+        implementation: new_lb_pair_implementation.clone(),
+    };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
+    }
+
+    _set_lb_pair_implementation(deps, new_lb_pair_implementation)
+}
 
+/// Internal function to set the LbPair implementation details.
+fn _set_lb_pair_implementation(
+    deps: DepsMut,
+    new_lb_pair_implementation: Implementation,
+) -> Result<Response> {
     let old_lb_pair_implementation = LB_PAIR_IMPLEMENTATION.load(deps.storage)?;
     if old_lb_pair_implementation == new_lb_pair_implementation {
         return Err(Error::SameImplementation {
@@ -69,13 +85,7 @@ pub fn set_lb_token_implementation(
     info: MessageInfo,
     new_lb_token_implementation: Implementation,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     let old_lb_token_implementation = LB_TOKEN_IMPLEMENTATION.load(deps.storage)?;
     if old_lb_token_implementation == new_lb_token_implementation {
@@ -108,8 +118,6 @@ pub fn create_lb_pair(
     viewing_key: String,
     entropy: String,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-
     // TODO: I think this is redundant
     // if !PRESETS.contains(deps.storage, &bin_step) {
     //     return Err(Error::BinStepHasNoPreset { bin_step });
@@ -119,7 +127,7 @@ pub fn create_lb_pair(
         .get(deps.storage, &bin_step)
         .ok_or(Error::BinStepHasNoPreset { bin_step })?;
 
-    let is_owner = info.sender == config.owner;
+    let is_owner = owner(deps.storage)? == info.sender;
 
     if !_is_preset_open(preset.0) && !is_owner {
         return Err(Error::PresetIsLockedForUsers {
@@ -173,6 +181,9 @@ pub fn create_lb_pair(
         return Err(Error::ImplementationNotSet);
     }
 
+    // The factory is the admin of the pairs, so it can migrate them to new implementations.
+    let admin = env.contract.address.to_string();
+
     let msg = SubMsg::reply_on_success(
         WasmMsg::Instantiate {
             code_id: lb_pair_implementation.id,
@@ -202,12 +213,10 @@ pub fn create_lb_pair(
                 lb_token_implementation,
                 viewing_key,
                 entropy,
-                admin_auth: config.admin_auth.into(),
-                query_auth: config.query_auth.into(),
             })?,
             code_hash: lb_pair_implementation.code_hash.clone(),
             funds: vec![],
-            admin: None, // TODO: should this be set to something else?
+            admin: Some(admin),
         },
         CREATE_LB_PAIR_REPLY_ID,
     );
@@ -285,9 +294,20 @@ pub fn create_lb_pair_part2(deps: DepsMut, _env: Env, reply_data: Binary) -> Res
         ALL_LB_PAIRS.get_len(deps.storage)? - 1,
     );
 
-    Ok(Response::default()
+    let mut response = Response::default()
         .set_data(to_binary(&lb_pair)?)
-        .add_event(event))
+        .add_event(event);
+
+    // New pairs inherit the status set on all pairs.
+    let status = PAIRS_STATUS
+        .may_load(deps.storage)?
+        .unwrap_or(LbPairContractStatus::Active);
+
+    if status != LbPairContractStatus::Active {
+        response = response.add_message(ILbPair(lb_pair.contract).set_contract_status(status)?);
+    }
+
+    Ok(response)
 }
 
 /// Function to set whether the pair is ignored or not for routing, it will make the pair unusable by the router.
@@ -305,13 +325,7 @@ pub fn set_lb_pair_ignored(
     bin_step: u16,
     ignored: bool,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     let (token_a, token_b) = _sort_tokens(token_x.clone(), token_y.clone());
 
@@ -378,13 +392,7 @@ pub fn set_preset(
     max_volatility_accumulator: u32,
     is_open: bool,
 ) -> Result<Response> {
-    let state = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &state.admin_auth.clone().into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
     if bin_step < MIN_BIN_STEP as u16 {
         return Err(Error::BinStepTooLow { bin_step });
     }
@@ -408,7 +416,6 @@ pub fn set_preset(
     PRESET_BIN_STEPS.insert(deps.storage, &bin_step)?;
 
     PRESETS.insert(deps.storage, &bin_step, &preset)?;
-    STATE.save(deps.storage, &state)?;
 
     let event = Event::preset_set(
         bin_step,
@@ -433,13 +440,7 @@ pub fn set_preset_open_state(
     bin_step: u16,
     is_open: bool,
 ) -> Result<Response> {
-    let state = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &state.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     let Some(mut preset) = PRESETS.get(deps.storage, &bin_step) else {
         return Err(Error::BinStepHasNoPreset { bin_step });
@@ -466,13 +467,7 @@ pub fn remove_preset(
     info: MessageInfo,
     bin_step: u16,
 ) -> Result<Response> {
-    let state = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &state.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     if !PRESETS.contains(deps.storage, &bin_step) {
         return Err(Error::BinStepHasNoPreset { bin_step });
@@ -503,7 +498,7 @@ pub fn remove_preset(
 /// * `max_volatility_accumulator` - The max value of volatility accumulator
 pub fn set_fee_parameters_on_pair(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     token_x: TokenType,
     token_y: TokenType,
@@ -516,13 +511,49 @@ pub fn set_fee_parameters_on_pair(
     protocol_share: u16,
     max_volatility_accumulator: u32,
 ) -> Result<Response> {
-    let state = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &state.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
+
+    let fee_parameters = StaticFeeParameters {
+        base_factor,
+        filter_period,
+        decay_period,
+        reduction_factor,
+        variable_fee_control,
+        protocol_share,
+        max_volatility_accumulator,
+    };
+
+    let operation = TimelockedOperation::SetFeeParametersOnPair {
+        token_x: token_x.clone(),
+        token_y: token_y.clone(),
+        bin_step,
+        fee_parameters: fee_parameters.clone(),
+    };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
+    }
+
+    _set_fee_parameters_on_pair(deps, token_x, token_y, bin_step, fee_parameters)
+}
+
+/// Internal function to set the static fee parameters of a pair.
+fn _set_fee_parameters_on_pair(
+    deps: DepsMut,
+    token_x: TokenType,
+    token_y: TokenType,
+    bin_step: u16,
+    fee_parameters: StaticFeeParameters,
+) -> Result<Response> {
+    let StaticFeeParameters {
+        base_factor,
+        filter_period,
+        decay_period,
+        reduction_factor,
+        variable_fee_control,
+        protocol_share,
+        max_volatility_accumulator,
+    } = fee_parameters;
+
     let (token_a, token_b) = _sort_tokens(token_x, token_y);
     let lb_pair = LB_PAIRS_INFO
         .get(
@@ -565,18 +596,19 @@ pub fn set_lb_hooks_parameters_on_pair(
     hooks_parameters: HooksParameters,
     on_hooks_set_data: Binary,
 ) -> Result<Response> {
-    // TODO: add this modifier
-    //     onlyRole(LB_HOOKS_MANAGER_ROLE)
+    only_role(deps.storage, LB_HOOKS_MANAGER_ROLE, &info.sender)?;
 
-    // original:
-    // if (Hooks.getHooks(hooksParameters) == address(0) || Hooks.getFlags(hooksParameters) == 0) {
-    //     revert LBFactory__InvalidHooksParameters();
-    // }
+    _verify_hooks(deps.as_ref(), &hooks_parameters)?;
 
-    // NOTE: Instead of checking if the address is 0, we check if the address is valid.
-    // TODO: Find a way to verify that the code_hash is correct for the given address.
-    if deps.api.addr_validate(&hooks_parameters.address).is_err() || hooks_parameters.flags == 0 {
-        return Err(Error::InvalidHooksParameters);
+    let operation = TimelockedOperation::SetLBHooksParametersOnPair {
+        token_x: token_x.clone(),
+        token_y: token_y.clone(),
+        bin_step,
+        hooks_parameters: hooks_parameters.clone(),
+        on_hooks_set_data: on_hooks_set_data.clone(),
+    };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
     }
 
     _set_lb_hooks_parameters_on_pair(
@@ -603,12 +635,64 @@ pub fn remove_lb_hooks_on_pair(
     token_y: TokenType,
     bin_step: u16,
 ) -> Result<Response> {
-    // TODO: add this modifier
-    //     onlyRole(LB_HOOKS_MANAGER_ROLE)
+    only_role(deps.storage, LB_HOOKS_MANAGER_ROLE, &info.sender)?;
+
+    let operation = TimelockedOperation::RemoveLBHooksOnPair {
+        token_x: token_x.clone(),
+        token_y: token_y.clone(),
+        bin_step,
+    };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
+    }
 
     _set_lb_hooks_parameters_on_pair(deps, env, info, token_x, token_y, bin_step, None, None)
 }
 
+/// Function to add a hooks code to the whitelist.
+/// Needs to be called by the owner.
+pub fn add_hooks_to_whitelist(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    hooks_implementation: Implementation,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    let Implementation { id, code_hash } = hooks_implementation;
+
+    if HOOKS_WHITELIST.contains(deps.storage, &id) {
+        return Err(Error::HooksAlreadyWhitelisted { code_id: id });
+    }
+
+    HOOKS_WHITELIST.insert(deps.storage, &id, &code_hash)?;
+
+    let event = Event::hooks_whitelisted(id, code_hash);
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Function to remove a hooks code from the whitelist.
+/// Needs to be called by the owner.
+pub fn remove_hooks_from_whitelist(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    code_id: u64,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    if !HOOKS_WHITELIST.contains(deps.storage, &code_id) {
+        return Err(Error::HooksNotWhitelisted { code_id });
+    }
+
+    HOOKS_WHITELIST.remove(deps.storage, &code_id)?;
+
+    let event = Event::hooks_removed_from_whitelist(code_id);
+
+    Ok(Response::new().add_event(event))
+}
+
 /// Function to set the recipient of the fees. This address needs to be able to receive SNIP20s.
 pub fn set_fee_recipient(
     deps: DepsMut,
@@ -616,13 +700,7 @@ pub fn set_fee_recipient(
     info: MessageInfo,
     fee_recipient: Addr,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     let old_fee_recipient = FEE_RECIPIENT.load(deps.storage)?;
     if old_fee_recipient == fee_recipient {
@@ -645,10 +723,22 @@ pub fn set_fee_recipient(
 /// - The flash_loan_fee is above the maximum flash loan fee
 pub fn set_flash_loan_fee(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     flash_loan_fee: Uint128,
 ) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    let operation = TimelockedOperation::SetFlashLoanFee { flash_loan_fee };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
+    }
+
+    _set_flash_loan_fee(deps, flash_loan_fee)
+}
+
+/// Internal function to set the flash loan fee.
+fn _set_flash_loan_fee(deps: DepsMut, flash_loan_fee: Uint128) -> Result<Response> {
     let old_flash_loan_fee = FLASH_LOAN_FEE.load(deps.storage)?;
 
     if old_flash_loan_fee == flash_loan_fee {
@@ -678,13 +768,7 @@ pub fn add_quote_asset(
     info: MessageInfo,
     quote_asset: TokenType,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
     if QUOTE_ASSET_WHITELIST
         .iter(deps.storage)?
         .any(|result| match result {
@@ -711,13 +795,7 @@ pub fn remove_quote_asset(
     info: MessageInfo,
     asset: TokenType,
 ) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
     // TODO: there has to be a better way to write this
     let found_asset = QUOTE_ASSET_WHITELIST
         .iter(deps.storage)?
@@ -742,13 +820,7 @@ pub fn remove_quote_asset(
 /// Function to force the decay of the volatility accumulator of a pair.
 /// Needs to be called by the owner.
 pub fn force_decay(deps: DepsMut, _env: Env, info: MessageInfo, pair: LbPair) -> Result<Response> {
-    let config = STATE.load(deps.storage)?;
-    validate_admin(
-        &deps.querier,
-        AdminPermissions::LiquidityBookAdmin,
-        info.sender.to_string(),
-        &config.admin_auth.into(),
-    )?;
+    only_owner(deps.storage, &info.sender)?;
 
     let (token_a, token_b) = _sort_tokens(pair.token_x, pair.token_y);
     let lb_pair = LB_PAIRS_INFO
@@ -775,6 +847,348 @@ pub fn force_decay(deps: DepsMut, _env: Env, info: MessageInfo, pair: LbPair) ->
     Ok(response)
 }
 
+/// Function to pause or unpause operations on a pair, such as swaps or flash loans.
+///
+/// Needs to be called by the owner.
+/// Reverts if the pair doesn't exist.
+pub fn set_paused_operations_on_pair(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    token_x: TokenType,
+    token_y: TokenType,
+    bin_step: u16,
+    operations: u8,
+    paused: bool,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    let (token_a, token_b) = _sort_tokens(token_x, token_y);
+    let lb_pair = LB_PAIRS_INFO
+        .get(
+            deps.storage,
+            &(token_a.unique_key(), token_b.unique_key(), bin_step),
+        )
+        .ok_or_else(|| Error::LbPairNotCreated {
+            token_x: token_a.unique_key(),
+            token_y: token_b.unique_key(),
+            bin_step,
+        })?
+        .lb_pair;
+
+    let msg = ILbPair(lb_pair.contract).set_paused_operations(operations, paused)?;
+
+    Ok(Response::new().add_message(msg))
+}
+
+/// Migrates a page of the pairs created by the factory to the current LbPair implementation.
+/// If the migration is timelocked, the pairs are migrated to the implementation set when it was
+/// queued, even if it has changed since.
+///
+/// Needs to be called by the owner. Only the pairs instantiated with the factory as their admin
+/// can be migrated.
+///
+/// # Arguments
+///
+/// * `start_after` - The index of the last pair migrated by the previous call, if any
+/// * `limit` - The maximum number of pairs to migrate
+pub fn migrate_lb_pairs(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    let lb_pair_implementation = LB_PAIR_IMPLEMENTATION.load(deps.storage)?;
+
+    if lb_pair_implementation.id == 0 {
+        return Err(Error::ImplementationNotSet);
+    }
+
+    let operation = TimelockedOperation::MigrateLbPairs {
+        implementation: lb_pair_implementation.clone(),
+        start_after,
+        limit,
+    };
+    if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+        return Ok(response);
+    }
+
+    _migrate_lb_pairs(deps, lb_pair_implementation, start_after, limit)
+}
+
+/// Internal function to migrate a page of pairs to `lb_pair_implementation`.
+fn _migrate_lb_pairs(
+    deps: DepsMut,
+    lb_pair_implementation: Implementation,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Response> {
+    let start = start_after.map_or(0, |index| index + 1);
+    let limit = limit
+        .unwrap_or(DEFAULT_LB_PAIRS_LIMIT)
+        .min(MAX_LB_PAIRS_LIMIT);
+    let end = ALL_LB_PAIRS
+        .get_len(deps.storage)?
+        .min(start.saturating_add(limit));
+
+    let mut messages = Vec::new();
+
+    for index in start..end {
+        let lb_pair = ALL_LB_PAIRS.get_at(deps.storage, index)?;
+        messages.push(ILbPair(lb_pair.contract).migrate(&lb_pair_implementation)?);
+    }
+
+    let mut response = Response::new().add_messages(messages).add_attribute(
+        "lb_pair_implementation",
+        lb_pair_implementation.id.to_string(),
+    );
+
+    if end > start {
+        response = response.add_attribute("last_migrated_index", (end - 1).to_string());
+    }
+
+    Ok(response)
+}
+
+/// Sets the contract status of the pairs created by the factory, by their index in the list of
+/// all pairs, and records it for the pairs created afterwards.
+/// Needs to be called by the owner.
+pub fn set_status_on_all_pairs(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    status: LbPairContractStatus,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    PAIRS_STATUS.save(deps.storage, &status)?;
+
+    let start = start_after.map_or(0, |index| index + 1);
+    let limit = limit
+        .unwrap_or(DEFAULT_LB_PAIRS_LIMIT)
+        .min(MAX_LB_PAIRS_LIMIT);
+    let end = ALL_LB_PAIRS
+        .get_len(deps.storage)?
+        .min(start.saturating_add(limit));
+
+    let mut messages = Vec::new();
+
+    for index in start..end {
+        let lb_pair = ALL_LB_PAIRS.get_at(deps.storage, index)?;
+        messages.push(ILbPair(lb_pair.contract).set_contract_status(status.clone())?);
+    }
+
+    let mut response = Response::new()
+        .add_messages(messages)
+        .add_attribute("pairs_status", status.to_string());
+
+    if end > start {
+        response = response.add_attribute("last_updated_index", (end - 1).to_string());
+    }
+
+    Ok(response)
+}
+
+/// Sets the delay before the timelocked operations can be executed.
+/// Needs to be called by the owner.
+///
+/// Decreasing the delay is itself timelocked, so that the owner can't skip the current delay.
+pub fn set_timelock_delay(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delay: u64,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    if delay > MAX_TIMELOCK_DELAY {
+        return Err(Error::TimelockDelayAboveMax {
+            delay,
+            max_delay: MAX_TIMELOCK_DELAY,
+        });
+    }
+
+    if delay < TIMELOCK_DELAY.may_load(deps.storage)?.unwrap_or_default() {
+        let operation = TimelockedOperation::SetTimelockDelay { delay };
+        if let Some(response) = _queue_if_timelocked(deps.storage, &env, operation)? {
+            return Ok(response);
+        }
+    }
+
+    _set_timelock_delay(deps, delay)
+}
+
+/// Internal function to set the timelock delay.
+fn _set_timelock_delay(deps: DepsMut, delay: u64) -> Result<Response> {
+    let old_delay = TIMELOCK_DELAY.may_load(deps.storage)?.unwrap_or_default();
+
+    TIMELOCK_DELAY.save(deps.storage, &delay)?;
+
+    let event = Event::timelock_delay_set(old_delay, delay);
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Executes a timelocked operation once its delay has passed.
+/// Can be called by anyone.
+pub fn execute_timelocked_operation(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response> {
+    let Some(PendingOperation { operation, eta, .. }) = PENDING_OPERATIONS.get(deps.storage, &id)
+    else {
+        return Err(Error::TimelockedOperationNotFound { id });
+    };
+
+    if env.block.time.seconds() < eta {
+        return Err(Error::TimelockedOperationNotReady { id, eta });
+    }
+
+    PENDING_OPERATIONS.remove(deps.storage, &id)?;
+
+    let response = match operation {
+        TimelockedOperation::SetLbPairImplementation { implementation } => {
+            if CONTRACT_STATUS.load(deps.storage)? == ContractStatus::FreezeAll {
+                return Err(Error::TransactionBlock());
+            }
+
+            _set_lb_pair_implementation(deps, implementation)?
+        }
+        TimelockedOperation::SetFeeParametersOnPair {
+            token_x,
+            token_y,
+            bin_step,
+            fee_parameters,
+        } => _set_fee_parameters_on_pair(deps, token_x, token_y, bin_step, fee_parameters)?,
+        TimelockedOperation::SetLBHooksParametersOnPair {
+            token_x,
+            token_y,
+            bin_step,
+            hooks_parameters,
+            on_hooks_set_data,
+        } => {
+            // The whitelist may have changed since the operation was queued.
+            _verify_hooks(deps.as_ref(), &hooks_parameters)?;
+
+            _set_lb_hooks_parameters_on_pair(
+                deps,
+                env,
+                info,
+                token_x,
+                token_y,
+                bin_step,
+                Some(hooks_parameters),
+                Some(on_hooks_set_data),
+            )?
+        }
+        TimelockedOperation::RemoveLBHooksOnPair {
+            token_x,
+            token_y,
+            bin_step,
+        } => _set_lb_hooks_parameters_on_pair(
+            deps, env, info, token_x, token_y, bin_step, None, None,
+        )?,
+        TimelockedOperation::MigrateLbPairs {
+            implementation,
+            start_after,
+            limit,
+        } => _migrate_lb_pairs(deps, implementation, start_after, limit)?,
+        TimelockedOperation::SetFlashLoanFee { flash_loan_fee } => {
+            _set_flash_loan_fee(deps, flash_loan_fee)?
+        }
+        TimelockedOperation::SetTimelockDelay { delay } => _set_timelock_delay(deps, delay)?,
+    };
+
+    Ok(response.add_event(Event::timelocked_operation_executed(id)))
+}
+
+/// Cancels a timelocked operation.
+/// Needs to be called by the owner.
+pub fn cancel_timelocked_operation(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response> {
+    only_owner(deps.storage, &info.sender)?;
+
+    if !PENDING_OPERATIONS.contains(deps.storage, &id) {
+        return Err(Error::TimelockedOperationNotFound { id });
+    }
+
+    PENDING_OPERATIONS.remove(deps.storage, &id)?;
+
+    let event = Event::timelocked_operation_cancelled(id);
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Internal function to queue `operation` if the timelock is enabled.
+///
+/// Returns `None` if the operation can be applied right away.
+fn _queue_if_timelocked(
+    storage: &mut dyn Storage,
+    env: &Env,
+    operation: TimelockedOperation,
+) -> Result<Option<Response>> {
+    let delay = TIMELOCK_DELAY.may_load(storage)?.unwrap_or_default();
+
+    if delay == 0 {
+        return Ok(None);
+    }
+
+    let id = NEXT_OPERATION_ID.may_load(storage)?.unwrap_or_default();
+    let eta = env.block.time.seconds() + delay;
+
+    PENDING_OPERATIONS.insert(storage, &id, &PendingOperation { id, operation, eta })?;
+    NEXT_OPERATION_ID.save(storage, &(id + 1))?;
+
+    let event = Event::timelocked_operation_queued(id, eta);
+
+    Ok(Some(Response::new().add_event(event)))
+}
+
+/// Internal function to check that the hooks parameters are valid, that the hooks contract was
+/// instantiated from a whitelisted code, and that its code hash is the whitelisted one.
+fn _verify_hooks(deps: Deps, hooks_parameters: &HooksParameters) -> Result<()> {
+    // original:
+    // if (Hooks.getHooks(hooksParameters) == address(0) || Hooks.getFlags(hooksParameters) == 0) {
+    //     revert LBFactory__InvalidHooksParameters();
+    // }
+
+    // NOTE: Instead of checking if the address is 0, we check if the address is valid.
+    if deps.api.addr_validate(&hooks_parameters.address).is_err() || hooks_parameters.flags == 0 {
+        return Err(Error::InvalidHooksParameters);
+    }
+
+    let contract_info: ContractInfoResponse =
+        deps.querier
+            .query(&QueryRequest::Wasm(WasmQuery::ContractInfo {
+                contract_addr: hooks_parameters.address.clone(),
+            }))?;
+    let code_id = contract_info.code_id;
+
+    let Some(code_hash) = HOOKS_WHITELIST.get(deps.storage, &code_id) else {
+        return Err(Error::HooksNotWhitelisted { code_id });
+    };
+
+    if !code_hash.eq_ignore_ascii_case(&hooks_parameters.code_hash) {
+        return Err(Error::HooksCodeHashMismatch {
+            code_id,
+            code_hash: hooks_parameters.code_hash.clone(),
+        });
+    }
+
+    Ok(())
+}
+
 /// Internal function to set a hooks contract to the pair
 pub fn _set_lb_hooks_parameters_on_pair(
     deps: DepsMut,
@@ -813,3 +1227,298 @@ pub fn _set_lb_hooks_parameters_on_pair(
 
     Ok(Response::new().add_message(msg))
 }
+
+/// Grants `role` to `account`.
+///
+/// Needs to be called by the owner.
+pub fn grant_lb_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: String,
+    account: String,
+) -> Result<Response> {
+    let account = deps.api.addr_validate(&account)?;
+    let event = grant_role(deps.storage, &info.sender, &role, &account)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Revokes `role` from `account`.
+///
+/// Needs to be called by the owner.
+pub fn revoke_lb_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: String,
+    account: String,
+) -> Result<Response> {
+    let account = deps.api.addr_validate(&account)?;
+    let event = revoke_role(deps.storage, &info.sender, &role, &account)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Revokes `role` from the caller.
+pub fn renounce_lb_role(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    role: String,
+) -> Result<Response> {
+    let event = renounce_role(deps.storage, &info.sender, &role)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Starts the ownership transfer of the factory to `new_owner`.
+///
+/// Needs to be called by the owner.
+pub fn transfer_factory_ownership(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response> {
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    let event = transfer_ownership(deps.storage, &info.sender, &new_owner)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Completes the ownership transfer of the factory.
+///
+/// Needs to be called by the pending owner.
+pub fn accept_factory_ownership(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response> {
+    let event = accept_ownership(deps.storage, &info.sender)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Leaves the factory without owner.
+///
+/// Needs to be called by the owner.
+pub fn renounce_factory_ownership(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response> {
+    let event = renounce_ownership(deps.storage, &info.sender)?;
+
+    Ok(Response::new().add_event(event))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::query::get_pending_operations;
+    use super::*;
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info},
+        ContractResult, SystemResult,
+    };
+    use liquidity_book::core::access_control::set_owner;
+
+    const DELAY: u64 = 100;
+
+    fn setup(deps: DepsMut) {
+        set_owner(deps.storage, &Addr::unchecked("owner")).unwrap();
+        FLASH_LOAN_FEE.save(deps.storage, &Uint128::zero()).unwrap();
+        set_timelock_delay(deps, mock_env(), mock_info("owner", &[]), DELAY).unwrap();
+    }
+
+    fn pending_ids(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> Vec<u64> {
+        get_pending_operations(deps, start_after, limit)
+            .unwrap()
+            .pending_operations
+            .into_iter()
+            .map(|pending_operation| pending_operation.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_timelocked_operation_executes_after_eta() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let owner = mock_info("owner", &[]);
+        let fee = Uint128::new(1_000_000_000_000_000);
+        set_flash_loan_fee(deps.as_mut(), mock_env(), owner.clone(), fee).unwrap();
+
+        assert_eq!(FLASH_LOAN_FEE.load(&deps.storage).unwrap(), Uint128::zero());
+        assert_eq!(pending_ids(deps.as_ref(), None, None), vec![0]);
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DELAY - 1);
+        let err =
+            execute_timelocked_operation(deps.as_mut(), env.clone(), owner.clone(), 0).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::TimelockedOperationNotReady { id: 0, .. }
+        ));
+
+        env.block.time = env.block.time.plus_seconds(1);
+        let anyone = mock_info("anyone", &[]);
+        execute_timelocked_operation(deps.as_mut(), env.clone(), anyone.clone(), 0).unwrap();
+
+        assert_eq!(FLASH_LOAN_FEE.load(&deps.storage).unwrap(), fee);
+        assert!(pending_ids(deps.as_ref(), None, None).is_empty());
+
+        let err = execute_timelocked_operation(deps.as_mut(), env, anyone, 0).unwrap_err();
+        assert!(matches!(err, Error::TimelockedOperationNotFound { id: 0 }));
+    }
+
+    #[test]
+    fn test_cancel_timelocked_operation() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let owner = mock_info("owner", &[]);
+        let fee = Uint128::new(1_000_000_000_000_000);
+        set_flash_loan_fee(deps.as_mut(), mock_env(), owner.clone(), fee).unwrap();
+
+        assert!(cancel_timelocked_operation(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            0
+        )
+        .is_err());
+        cancel_timelocked_operation(deps.as_mut(), mock_env(), owner.clone(), 0).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DELAY);
+        let err = execute_timelocked_operation(deps.as_mut(), env, owner, 0).unwrap_err();
+
+        assert!(matches!(err, Error::TimelockedOperationNotFound { id: 0 }));
+        assert_eq!(FLASH_LOAN_FEE.load(&deps.storage).unwrap(), Uint128::zero());
+    }
+
+    #[test]
+    fn test_timelock_delay_decrease_is_timelocked() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let owner = mock_info("owner", &[]);
+        set_timelock_delay(deps.as_mut(), mock_env(), owner.clone(), DELAY / 10).unwrap();
+
+        assert_eq!(TIMELOCK_DELAY.load(&deps.storage).unwrap(), DELAY);
+        assert_eq!(pending_ids(deps.as_ref(), None, None), vec![0]);
+
+        set_timelock_delay(deps.as_mut(), mock_env(), owner.clone(), DELAY * 2).unwrap();
+        assert_eq!(TIMELOCK_DELAY.load(&deps.storage).unwrap(), DELAY * 2);
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(DELAY);
+        execute_timelocked_operation(deps.as_mut(), env, owner, 0).unwrap();
+
+        assert_eq!(TIMELOCK_DELAY.load(&deps.storage).unwrap(), DELAY / 10);
+    }
+
+    #[test]
+    fn test_pending_operations_pagination() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let owner = mock_info("owner", &[]);
+        for fee in 1..=3u128 {
+            set_flash_loan_fee(deps.as_mut(), mock_env(), owner.clone(), Uint128::new(fee))
+                .unwrap();
+        }
+        cancel_timelocked_operation(deps.as_mut(), mock_env(), owner, 1).unwrap();
+
+        assert_eq!(pending_ids(deps.as_ref(), None, None), vec![0, 2]);
+        assert_eq!(pending_ids(deps.as_ref(), None, Some(1)), vec![0]);
+        assert_eq!(pending_ids(deps.as_ref(), Some(0), Some(1)), vec![2]);
+        assert!(pending_ids(deps.as_ref(), Some(2), None).is_empty());
+    }
+
+    fn hooks_parameters(address: &str, code_hash: &str) -> HooksParameters {
+        HooksParameters {
+            address: address.to_string(),
+            code_hash: code_hash.to_string(),
+            flags: 1,
+        }
+    }
+
+    #[test]
+    fn test_hooks_whitelist() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        let owner = mock_info("owner", &[]);
+        let implementation = Implementation {
+            id: 1,
+            code_hash: "hooks_hash".to_string(),
+        };
+
+        assert!(add_hooks_to_whitelist(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            implementation.clone()
+        )
+        .is_err());
+        add_hooks_to_whitelist(
+            deps.as_mut(),
+            mock_env(),
+            owner.clone(),
+            implementation.clone(),
+        )
+        .unwrap();
+        let err = add_hooks_to_whitelist(deps.as_mut(), mock_env(), owner.clone(), implementation)
+            .unwrap_err();
+        assert!(matches!(err, Error::HooksAlreadyWhitelisted { code_id: 1 }));
+
+        remove_hooks_from_whitelist(deps.as_mut(), mock_env(), owner.clone(), 1).unwrap();
+        let err = remove_hooks_from_whitelist(deps.as_mut(), mock_env(), owner, 1).unwrap_err();
+        assert!(matches!(err, Error::HooksNotWhitelisted { code_id: 1 }));
+    }
+
+    #[test]
+    fn test_verify_hooks() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut());
+
+        add_hooks_to_whitelist(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("owner", &[]),
+            Implementation {
+                id: 1,
+                code_hash: "hooks_hash".to_string(),
+            },
+        )
+        .unwrap();
+
+        // "hooks" was instantiated from the whitelisted code 1, "other_hooks" from code 2.
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { contract_addr } => {
+                let code_id = if contract_addr == "hooks" { 1 } else { 2 };
+                SystemResult::Ok(ContractResult::Ok(Binary::from(
+                    format!(r#"{{"code_id":{code_id},"creator":"creator","pinned":false}}"#)
+                        .into_bytes(),
+                )))
+            }
+            _ => panic!("unexpected query: {query:?}"),
+        });
+
+        _verify_hooks(deps.as_ref(), &hooks_parameters("hooks", "hooks_hash")).unwrap();
+        _verify_hooks(deps.as_ref(), &hooks_parameters("hooks", "HOOKS_HASH")).unwrap();
+
+        let err =
+            _verify_hooks(deps.as_ref(), &hooks_parameters("hooks", "other_hash")).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::HooksCodeHashMismatch { code_id: 1, .. }
+        ));
+
+        let err = _verify_hooks(
+            deps.as_ref(),
+            &hooks_parameters("other_hooks", "hooks_hash"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::HooksNotWhitelisted { code_id: 2 }));
+
+        let mut no_flags = hooks_parameters("hooks", "hooks_hash");
+        no_flags.flags = 0;
+        let err = _verify_hooks(deps.as_ref(), &no_flags).unwrap_err();
+        assert!(matches!(err, Error::InvalidHooksParameters));
+    }
+}
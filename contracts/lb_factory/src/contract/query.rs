@@ -1,12 +1,18 @@
 use super::{
     helper::{_get_lb_pair_information, _is_preset_open, _sort_tokens},
     state::*,
-    MAX_FLASH_LOAN_FEE, MIN_BIN_STEP, OFFSET_IS_PRESET_OPEN,
+    DEFAULT_LB_PAIRS_LIMIT, DEFAULT_PENDING_OPERATIONS_LIMIT, MAX_FLASH_LOAN_FEE,
+    MAX_LB_PAIRS_LIMIT, MAX_PENDING_OPERATIONS_LIMIT, MIN_BIN_STEP, OFFSET_IS_PRESET_OPEN,
 };
 use crate::{Error, Result};
 use cosmwasm_std::{Deps, StdResult};
 use liquidity_book::{
-    core::TokenType, interfaces::lb_factory::*, libraries::math::encoded::Encoded,
+    core::TokenType,
+    interfaces::{
+        lb_factory::*,
+        lb_pair::{ContractStatus as LbPairContractStatus, ILbPair},
+    },
+    libraries::math::encoded::Encoded,
 };
 
 /// Get the minimum bin step a pair can have.
@@ -139,6 +145,56 @@ pub fn get_preset(deps: Deps, bin_step: u16) -> Result<PresetResponse> {
     })
 }
 
+/// Returns the delay before the timelocked operations can be executed.
+pub fn get_timelock_delay(deps: Deps) -> Result<TimelockDelayResponse> {
+    let delay = TIMELOCK_DELAY.may_load(deps.storage)?.unwrap_or_default();
+
+    Ok(TimelockDelayResponse { delay })
+}
+
+/// Returns the timelocked operations waiting to be executed.
+///
+/// The operations are returned by increasing id, starting after the id `start_after`.
+pub fn get_pending_operations(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<PendingOperationsResponse> {
+    let start = start_after.map_or(0, |id| id + 1);
+    let limit = limit
+        .unwrap_or(DEFAULT_PENDING_OPERATIONS_LIMIT)
+        .min(MAX_PENDING_OPERATIONS_LIMIT) as usize;
+    let next_operation_id = NEXT_OPERATION_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default();
+
+    // NOTE: The ids are looked up one by one because the order of the keymap changes when an
+    // operation is removed. The ids of executed and cancelled operations are skipped.
+    let mut pending_operations = Vec::new();
+
+    for id in start..next_operation_id {
+        if pending_operations.len() == limit {
+            break;
+        }
+
+        if let Some(pending_operation) = PENDING_OPERATIONS.get(deps.storage, &id) {
+            pending_operations.push(pending_operation);
+        }
+    }
+
+    Ok(PendingOperationsResponse { pending_operations })
+}
+
+/// Returns the hooks codes that pairs are allowed to use.
+pub fn get_hooks_whitelist(deps: Deps) -> Result<HooksWhitelistResponse> {
+    let hooks_whitelist = HOOKS_WHITELIST
+        .iter(deps.storage)?
+        .map(|result| result.map(|(id, code_hash)| Implementation { id, code_hash }))
+        .collect::<StdResult<Vec<Implementation>>>()?;
+
+    Ok(HooksWhitelistResponse { hooks_whitelist })
+}
+
 /// Returns the list of available bin steps with a preset.
 pub fn get_all_bin_steps(deps: Deps) -> Result<AllBinStepsResponse> {
     let bin_step_with_preset: Vec<u16> = PRESET_BIN_STEPS
@@ -215,3 +271,91 @@ pub fn get_all_lb_pairs(
     // NOTE: This cannot fail, but I'm keeping it `Result` to match all the other queries.
     Ok(AllLbPairsResponse { lb_pairs_available })
 }
+
+/// Returns the status set on all pairs, and the current status of each pair, by their index in
+/// the list of all pairs.
+pub fn get_pairs_status(
+    deps: Deps,
+    start_after: Option<u32>,
+    limit: Option<u32>,
+) -> Result<PairsStatusResponse> {
+    let status = PAIRS_STATUS
+        .may_load(deps.storage)?
+        .unwrap_or(LbPairContractStatus::Active);
+
+    let start = start_after.map_or(0, |index| index + 1);
+    let limit = limit
+        .unwrap_or(DEFAULT_LB_PAIRS_LIMIT)
+        .min(MAX_LB_PAIRS_LIMIT);
+    let end = ALL_LB_PAIRS
+        .get_len(deps.storage)?
+        .min(start.saturating_add(limit));
+
+    let mut lb_pairs = Vec::new();
+
+    for index in start..end {
+        let lb_pair = ALL_LB_PAIRS.get_at(deps.storage, index)?;
+        // A single pair that can't be queried, e.g. one that was never migrated, must not hide
+        // the status of the others.
+        let contract_status = ILbPair(lb_pair.contract.clone())
+            .get_contract_status(deps.querier)
+            .ok();
+
+        lb_pairs.push(LbPairStatus {
+            lb_pair,
+            contract_status,
+        });
+    }
+
+    Ok(PairsStatusResponse { status, lb_pairs })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{
+        testing::mock_dependencies, to_binary, Addr, ContractResult, SystemError, SystemResult,
+        WasmQuery,
+    };
+    use liquidity_book::{interfaces::lb_pair::ContractStatusResponse, testing::mock_lb_pair};
+
+    #[test]
+    fn test_pairs_status_reports_unknown_pair() {
+        let mut deps = mock_dependencies();
+
+        let lb_pair = mock_lb_pair();
+        let mut old_lb_pair = mock_lb_pair();
+        old_lb_pair.contract.address = Addr::unchecked("old_lb_pair");
+        ALL_LB_PAIRS.push(&mut deps.storage, &lb_pair).unwrap();
+        ALL_LB_PAIRS.push(&mut deps.storage, &old_lb_pair).unwrap();
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "lb_pair" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&ContractStatusResponse {
+                        contract_status: LbPairContractStatus::FreezeAll,
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let response = get_pairs_status(deps.as_ref(), None, None).unwrap();
+
+        assert_eq!(response.status, LbPairContractStatus::Active);
+        assert_eq!(
+            response.lb_pairs,
+            vec![
+                LbPairStatus {
+                    lb_pair,
+                    contract_status: Some(LbPairContractStatus::FreezeAll),
+                },
+                LbPairStatus {
+                    lb_pair: old_lb_pair,
+                    contract_status: None,
+                },
+            ]
+        );
+    }
+}
@@ -3,8 +3,8 @@ use cosmwasm_std::{Addr, ContractInfo, Uint128};
 use liquidity_book::{
     core::TokenType,
     interfaces::{
-        lb_factory::{Implementation, LbPairInformation},
-        lb_pair::LbPair,
+        lb_factory::{Implementation, LbPairInformation, PendingOperation},
+        lb_pair::{ContractStatus as LbPairContractStatus, LbPair},
     },
     libraries::pair_parameter_helper::PairParameters,
 };
@@ -15,7 +15,11 @@ use secret_toolkit::{
 use std::collections::HashSet;
 
 pub static STATE: Item<State> = Item::new(b"state");
+/// The state of the factories instantiated before contract versions were stored.
+pub static LEGACY_STATE: Item<LegacyState> = Item::new(b"state");
 pub static CONTRACT_STATUS: Item<ContractStatus, Json> = Item::new(b"contract_status");
+/// The contract status set on all pairs, that new pairs inherit. Active if it was never set.
+pub static PAIRS_STATUS: Item<LbPairContractStatus, Json> = Item::new(b"pairs_status");
 
 pub static FEE_RECIPIENT: Item<Addr> = Item::new(b"fee_recipient");
 pub static FLASH_LOAN_FEE: Item<Uint128> = Item::new(b"flashloan_fee");
@@ -42,6 +46,16 @@ pub static PRESETS: Keymap<u16, PairParameters> = Keymap::new(b"presets");
 pub static QUOTE_ASSET_WHITELIST: AppendStore<TokenType, Json> =
     AppendStore::new(b"quote_asset_whitelist");
 
+/// The delay, in seconds, before the timelocked operations can be executed. Disabled if zero or
+/// never set.
+pub static TIMELOCK_DELAY: Item<u64> = Item::new(b"timelock_delay");
+pub static NEXT_OPERATION_ID: Item<u64> = Item::new(b"next_operation_id");
+pub static PENDING_OPERATIONS: Keymap<u64, PendingOperation, Json> =
+    Keymap::new(b"pending_operations");
+
+/// Mapping from the code ID of an approved hooks contract to its code hash.
+pub static HOOKS_WHITELIST: Keymap<u64, String> = Keymap::new(b"hooks_whitelist");
+
 // TODO: is this good?
 // The Hashset<u16> will represent the "EnumerableSet.UintSet" from solidity.
 // "The primary purpose of EnumerableSet.UintSet is to provide a convenient way to store, iterate, and retrieve
@@ -65,8 +79,12 @@ pub enum ContractStatus {
 #[cw_serde]
 pub struct State {
     pub contract_info: ContractInfo,
+}
+
+#[cw_serde]
+pub struct LegacyState {
+    pub contract_info: ContractInfo,
     pub owner: Addr,
-    // TODO: change to ContractInfo, or maybe get rid of these auth contracts...
     pub admin_auth: ContractInfo,
     pub query_auth: ContractInfo,
 }
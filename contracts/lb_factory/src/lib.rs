@@ -6,7 +6,7 @@
 
 mod contract;
 
-pub use contract::{execute, instantiate, query, reply};
+pub use contract::{execute, instantiate, migrate, query, reply};
 
 // Use this crate's custom Error type
 pub use liquidity_book::interfaces::lb_factory::LbFactoryError as Error;
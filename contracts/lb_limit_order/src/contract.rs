@@ -0,0 +1,555 @@
+use crate::{execute::*, query::*, state::*, Error, Result};
+use cosmwasm_std::{
+    entry_point, from_binary, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, SubMsgResult, Uint128,
+};
+use liquidity_book::{
+    core::contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+    interfaces::{
+        lb_factory::ILbFactory,
+        lb_limit_order::{
+            ExecuteMsg, InstantiateMsg, InvokeMsg, MigrateMsg, QueryMsg, QueryWithPermit,
+        },
+        lb_pair,
+    },
+};
+use secret_toolkit::{
+    crypto::sha_256,
+    permit::Permit,
+    snip20,
+    viewing_key::{ViewingKey, ViewingKeyStore},
+};
+
+pub const MINT_REPLY_ID: u64 = 1u64;
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[entry_point]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response> {
+    ViewingKey::set_seed(deps.storage, &sha_256(msg.entropy.as_bytes()));
+
+    let factory = msg.factory.validate(deps.api)?;
+
+    FACTORY.save(deps.storage, &ILbFactory(factory))?;
+    NEXT_ORDER_ID.save(deps.storage, &0)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new())
+}
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
+#[entry_point]
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<Response> {
+    match msg {
+        ExecuteMsg::PlaceOrder { params } => {
+            let [coin] = info.funds.as_slice() else {
+                return Err(Error::InvalidFunds);
+            };
+            let (denom, amount) = (coin.denom.clone(), coin.amount);
+
+            place_order(deps, env, info.sender, denom, true, amount, params)
+        }
+        ExecuteMsg::ExecuteOrders { order_ids } => execute_orders(deps, env, info, order_ids),
+        ExecuteMsg::CancelOrder { order_id } => cancel_order(deps, env, info, order_id),
+        ExecuteMsg::Register { address, code_hash } => register(deps, env, address, code_hash),
+        ExecuteMsg::Receive {
+            sender: _,
+            from,
+            amount,
+            memo: _,
+            msg,
+        } => receive(deps, env, info, from, amount, msg),
+        ExecuteMsg::CreateViewingKey { entropy } => create_viewing_key(deps, env, info, entropy),
+        ExecuteMsg::SetViewingKey { key } => set_viewing_key(deps, info, key),
+        ExecuteMsg::RevokePermit { permit_name } => revoke_permit(deps, info, permit_name),
+    }
+}
+
+pub fn register(deps: DepsMut, env: Env, address: String, code_hash: String) -> Result<Response> {
+    deps.api.addr_validate(&address)?;
+
+    let msg = snip20::register_receive_msg(env.contract.code_hash, None, 1, code_hash, address)?;
+
+    Ok(Response::new().add_message(msg))
+}
+
+pub fn receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from: Addr,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response> {
+    match from_binary(&msg)? {
+        // The sender of a SNIP-20 receive message is the token contract.
+        InvokeMsg::PlaceOrder { params } => place_order(
+            deps,
+            env,
+            from,
+            info.sender.to_string(),
+            false,
+            amount,
+            params,
+        ),
+    }
+}
+
+#[entry_point]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
+    match msg {
+        QueryMsg::GetFactory {} => to_binary(&get_factory(deps)?),
+        QueryMsg::GetOrder {
+            order_id,
+            account,
+            key,
+        } => {
+            let account = check_viewing_key(deps, &account, &key)?;
+            to_binary(&get_order(deps, &account, order_id)?)
+        }
+        QueryMsg::GetUserOrders {
+            account,
+            key,
+            page,
+            page_size,
+        } => {
+            let account = check_viewing_key(deps, &account, &key)?;
+            to_binary(&get_user_orders(deps, &account, page, page_size)?)
+        }
+        QueryMsg::WithPermit { permit, query } => return permit_queries(deps, env, permit, query),
+    }
+    .map_err(Error::StdError)
+}
+
+fn permit_queries(deps: Deps, env: Env, permit: Permit, query: QueryWithPermit) -> Result<Binary> {
+    let account = validate_permit(deps, &env, &permit)?;
+
+    match query {
+        QueryWithPermit::GetOrder { order_id } => to_binary(&get_order(deps, &account, order_id)?),
+        QueryWithPermit::GetUserOrders { page, page_size } => {
+            to_binary(&get_user_orders(deps, &account, page, page_size)?)
+        }
+    }
+    .map_err(Error::StdError)
+}
+
+#[entry_point]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response> {
+    match (msg.id, msg.result) {
+        (MINT_REPLY_ID, SubMsgResult::Ok(s)) => match s.data {
+            Some(data) => {
+                let lb_pair::MintResponse {
+                    liquidity_minted, ..
+                } = from_binary(&data)?;
+
+                let mut order = EPHEMERAL_ORDER.load(deps.storage)?;
+                EPHEMERAL_ORDER.remove(deps.storage);
+
+                order.order_id = NEXT_ORDER_ID.load(deps.storage)?;
+                order.shares = liquidity_minted.first().copied().unwrap_or_default();
+
+                ORDERS.insert(deps.storage, &order.order_id, &order)?;
+                USER_ORDERS
+                    .add_suffix(order.owner.as_bytes())
+                    .insert(deps.storage, &order.order_id)?;
+                NEXT_ORDER_ID.save(deps.storage, &(order.order_id + 1))?;
+
+                Ok(Response::new()
+                    .add_attribute("order_id", order.order_id.to_string())
+                    .set_data(to_binary(&order.order_id)?))
+            }
+            None => Err(Error::ReplyDataMissing),
+        },
+        _ => Err(Error::UnknownReplyId { id: msg.id }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        ContractResult, CosmosMsg, OwnedDeps, SubMsgResponse, SystemResult, Uint256, WasmMsg,
+        WasmQuery,
+    };
+    use liquidity_book::{
+        core::RawContract,
+        interfaces::{
+            lb_factory::{LbPairInformation, LbPairInformationResponse},
+            lb_limit_order::{
+                CreateViewingKeyResponse, OrderType, PlaceOrderParams, UserOrdersResponse,
+            },
+            lb_pair::{ActiveIdResponse, ILbPair, MintResponse},
+        },
+        testing::{mock_lb_pair, mock_token, ACTIVE_ID, BIN_STEP},
+    };
+
+    const ORDER_ID: u32 = ACTIVE_ID + 5;
+
+    fn set_active_id(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, active_id: u32) {
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { contract_addr, .. } = query else {
+                panic!("unexpected query {query:?}");
+            };
+            let response = if contract_addr == "factory" {
+                to_binary(&LbPairInformationResponse {
+                    lb_pair_information: LbPairInformation {
+                        bin_step: BIN_STEP,
+                        lb_pair: mock_lb_pair(),
+                        created_by_owner: true,
+                        ignored_for_routing: false,
+                    },
+                })
+            } else {
+                to_binary(&ActiveIdResponse { active_id })
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+    }
+
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        set_active_id(&mut deps, ACTIVE_ID);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("admin", &[]),
+            InstantiateMsg {
+                factory: RawContract {
+                    address: "factory".to_string(),
+                    code_hash: "factory_hash".to_string(),
+                },
+                entropy: "entropy".to_string(),
+            },
+        )
+        .unwrap();
+
+        deps
+    }
+
+    fn place_order(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Response {
+        let params = PlaceOrderParams {
+            token_x: mock_token("token_x"),
+            token_y: mock_token("token_y"),
+            bin_step: BIN_STEP,
+            id: ORDER_ID,
+            tip: Uint128::new(100),
+        };
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token_x", &[]),
+            ExecuteMsg::Receive {
+                sender: Addr::unchecked("owner"),
+                from: Addr::unchecked("owner"),
+                amount: Uint128::new(1_100),
+                memo: None,
+                msg: to_binary(&InvokeMsg::PlaceOrder { params }).unwrap(),
+            },
+        )
+        .unwrap()
+    }
+
+    fn mint_reply(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) -> Response {
+        let data = to_binary(&MintResponse {
+            amounts_received: [0u8; 32],
+            amounts_left: [0u8; 32],
+            liquidity_minted: vec![Uint256::from(1_000u128)],
+        })
+        .unwrap();
+
+        reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: MINT_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(data),
+                }),
+                was_orig_msg_encrypted: false,
+                is_encrypted: false,
+            },
+        )
+        .unwrap()
+    }
+
+    fn execute_as(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        sender: &str,
+        msg: ExecuteMsg,
+    ) -> Result<Response> {
+        execute(deps.as_mut(), mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn test_place_order() {
+        let mut deps = setup();
+
+        let response = place_order(&mut deps);
+
+        // The tokens without the tip are sent to the pair, then the pair mints in a submessage.
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(response.messages[1].id, MINT_REPLY_ID);
+
+        let order = EPHEMERAL_ORDER.load(deps.as_ref().storage).unwrap();
+        assert_eq!(order.order_type, OrderType::Ask);
+        assert_eq!(order.amount_in, Uint128::new(1_000));
+        assert_eq!(order.tip, Uint128::new(100));
+    }
+
+    #[test]
+    fn test_place_order_on_wrong_side_of_active_id() {
+        let mut deps = setup();
+        set_active_id(&mut deps, ORDER_ID + 1);
+
+        let params = PlaceOrderParams {
+            token_x: mock_token("token_x"),
+            token_y: mock_token("token_y"),
+            bin_step: BIN_STEP,
+            id: ORDER_ID,
+            tip: Uint128::zero(),
+        };
+        let err = receive(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("token_x", &[]),
+            Addr::unchecked("owner"),
+            Uint128::new(1_000),
+            to_binary(&InvokeMsg::PlaceOrder { params }).unwrap(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::InvalidOrderBin { .. }));
+    }
+
+    #[test]
+    fn test_reply_stores_order() {
+        let mut deps = setup();
+        place_order(&mut deps);
+
+        let response = mint_reply(&mut deps);
+        assert_eq!(response.data, Some(to_binary(&0u64).unwrap()));
+
+        assert!(EPHEMERAL_ORDER
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+        let order = ORDERS.get(deps.as_ref().storage, &0).unwrap();
+        assert_eq!(order.owner, Addr::unchecked("owner"));
+        assert_eq!(order.shares, Uint256::from(1_000u128));
+        assert_eq!(NEXT_ORDER_ID.load(deps.as_ref().storage).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_fill_detection() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        let order = ORDERS.get(deps.as_ref().storage, &0).unwrap();
+        assert!(!is_filled(deps.as_ref().querier, &order).unwrap());
+
+        // The active id reaching the bin of an ask is not enough, it must move past it.
+        set_active_id(&mut deps, ORDER_ID);
+        assert!(!is_filled(deps.as_ref().querier, &order).unwrap());
+
+        set_active_id(&mut deps, ORDER_ID + 1);
+        assert!(is_filled(deps.as_ref().querier, &order).unwrap());
+    }
+
+    #[test]
+    fn test_execute_orders_pays_tip() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        let msg = ExecuteMsg::ExecuteOrders { order_ids: vec![0] };
+
+        let err = execute_as(&mut deps, "keeper", msg.clone()).unwrap_err();
+        assert!(matches!(err, Error::OrderNotFilled { order_id: 0 }));
+
+        set_active_id(&mut deps, ORDER_ID + 1);
+        let response = execute_as(&mut deps, "keeper", msg.clone()).unwrap();
+
+        // The liquidity is burned to the owner, and the tip is sent to the keeper.
+        assert_eq!(response.messages.len(), 2);
+        let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) = &response.messages[0].msg
+        else {
+            panic!("expected a burn message");
+        };
+        assert_eq!(contract_addr, "lb_pair");
+        let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) = &response.messages[1].msg
+        else {
+            panic!("expected a tip transfer");
+        };
+        assert_eq!(contract_addr, "token_x");
+
+        assert!(ORDERS.get(deps.as_ref().storage, &0).is_none());
+        let err = execute_as(&mut deps, "keeper", msg).unwrap_err();
+        assert!(matches!(err, Error::OrderNotFound { order_id: 0 }));
+    }
+
+    #[test]
+    fn test_execute_crossed_order_pays_keeper_and_owner() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        // A swap crosses the bin of the ask, converting it to token Y.
+        set_active_id(&mut deps, ORDER_ID + 3);
+        let response = execute_as(
+            &mut deps,
+            "keeper",
+            ExecuteMsg::ExecuteOrders { order_ids: vec![0] },
+        )
+        .unwrap();
+
+        let env = mock_env();
+        let burn_to_owner = ILbPair(mock_lb_pair().contract)
+            .burn(
+                env.contract.address.to_string(),
+                "owner".to_string(),
+                vec![ORDER_ID],
+                vec![Uint256::from(1_000u128)],
+            )
+            .unwrap();
+        let tip_to_keeper = mock_token("token_x")
+            .transfer(Uint128::new(100), Addr::unchecked("keeper"))
+            .unwrap();
+
+        assert_eq!(response.messages[0].msg, CosmosMsg::from(burn_to_owner));
+        assert_eq!(response.messages[1].msg, tip_to_keeper);
+        assert_eq!(response.attributes[0].value, "0");
+    }
+
+    #[test]
+    fn test_cancel_order() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        let msg = ExecuteMsg::CancelOrder { order_id: 0 };
+
+        let err = execute_as(&mut deps, "keeper", msg.clone()).unwrap_err();
+        assert!(matches!(err, Error::OnlyOrderOwner { order_id: 0 }));
+
+        let response = execute_as(&mut deps, "owner", msg).unwrap();
+
+        // The liquidity is burned and the tip is refunded to the owner.
+        assert_eq!(response.messages.len(), 2);
+        assert!(ORDERS.get(deps.as_ref().storage, &0).is_none());
+        assert_eq!(
+            USER_ORDERS
+                .add_suffix(b"owner")
+                .get_len(deps.as_ref().storage)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_order_queries_require_viewing_key() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        let user_orders = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, key: &str| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetUserOrders {
+                    account: "owner".to_string(),
+                    key: key.to_string(),
+                    page: None,
+                    page_size: None,
+                },
+            )
+        };
+
+        let err = user_orders(&deps, "key").unwrap_err();
+        assert!(matches!(err, Error::Unauthorized));
+
+        execute_as(
+            &mut deps,
+            "owner",
+            ExecuteMsg::SetViewingKey {
+                key: "key".to_string(),
+            },
+        )
+        .unwrap();
+
+        let response: UserOrdersResponse =
+            from_binary(&user_orders(&deps, "key").unwrap()).unwrap();
+        assert_eq!(response.total, 1);
+        assert_eq!(response.orders[0].order.order_id, 0);
+
+        // Another account can't see the order, even with its own viewing key.
+        execute_as(
+            &mut deps,
+            "other",
+            ExecuteMsg::SetViewingKey {
+                key: "other_key".to_string(),
+            },
+        )
+        .unwrap();
+        let err = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::GetOrder {
+                order_id: 0,
+                account: "other".to_string(),
+                key: "other_key".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::StdError(StdError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_created_viewing_key_authenticates_queries() {
+        let mut deps = setup();
+        place_order(&mut deps);
+        mint_reply(&mut deps);
+
+        let response = execute_as(
+            &mut deps,
+            "owner",
+            ExecuteMsg::CreateViewingKey {
+                entropy: "owner_entropy".to_string(),
+            },
+        )
+        .unwrap();
+        let CreateViewingKeyResponse { key } = from_binary(&response.data.unwrap()).unwrap();
+
+        let response: UserOrdersResponse = from_binary(
+            &query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::GetUserOrders {
+                    account: "owner".to_string(),
+                    key,
+                    page: None,
+                    page_size: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(response.total, 1);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{contract::MINT_REPLY_ID, state::*, Error, Result};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, DepsMut, Env, MessageInfo, QuerierWrapper, Response,
+    StdResult, Storage, SubMsg, Uint128, Uint256, WasmMsg,
+};
+use liquidity_book::{
+    core::TokenType,
+    interfaces::{
+        lb_limit_order::{CreateViewingKeyResponse, Order, OrderType, PlaceOrderParams},
+        lb_pair::ILbPair,
+    },
+    libraries::{math::liquidity_configurations::PRECISION, LiquidityConfigurations},
+};
+use secret_toolkit::{
+    permit::RevokedPermits,
+    snip20,
+    viewing_key::{ViewingKey, ViewingKeyStore},
+};
+
+/// Places a limit order, by minting single-sided liquidity in the bin `params.id` of the pair.
+///
+/// An ask deposits token X above the active id and a bid deposits token Y below it, so the
+/// order is filled once the active id has moved past its bin.
+///
+/// # Arguments
+///
+/// * `owner` - The owner of the order
+/// * `token` - The address of the SNIP-20 token, or the denom of the native token, that was sent
+/// * `is_native` - Whether the token is a native token
+/// * `amount` - The amount sent, including the tip
+/// * `params` - The pair, bin and tip of the order
+pub fn place_order(
+    deps: DepsMut,
+    env: Env,
+    owner: Addr,
+    token: String,
+    is_native: bool,
+    amount: Uint128,
+    params: PlaceOrderParams,
+) -> Result<Response> {
+    let factory = FACTORY.load(deps.storage)?;
+    let lb_pair = factory
+        .get_lb_pair_information(
+            deps.querier,
+            params.token_x,
+            params.token_y,
+            params.bin_step,
+        )?
+        .lb_pair;
+
+    let is_token = |token_type: &TokenType| {
+        token_type.is_native_token() == is_native && token_type.unique_key() == token
+    };
+
+    let (order_type, token_in) = if is_token(&lb_pair.token_x) {
+        (OrderType::Ask, lb_pair.token_x)
+    } else if is_token(&lb_pair.token_y) {
+        (OrderType::Bid, lb_pair.token_y)
+    } else {
+        return Err(Error::WrongToken { token });
+    };
+
+    if params.tip >= amount {
+        return Err(Error::InvalidTip {
+            tip: params.tip,
+            amount,
+        });
+    }
+
+    let amount_in = amount - params.tip;
+    let pair = ILbPair(lb_pair.contract);
+    let active_id = pair.get_active_id(deps.querier)?;
+
+    let (distribution_x, distribution_y) = match order_type {
+        OrderType::Ask if params.id > active_id => (PRECISION, 0),
+        OrderType::Bid if params.id < active_id => (0, PRECISION),
+        _ => {
+            return Err(Error::InvalidOrderBin {
+                order_type,
+                id: params.id,
+                active_id,
+            })
+        }
+    };
+
+    let mut mint_msg = pair.mint(
+        env.contract.address.to_string(),
+        vec![LiquidityConfigurations::encode_params(
+            distribution_x,
+            distribution_y,
+            params.id,
+        )],
+        owner.to_string(),
+    )?;
+
+    let mut response = Response::new();
+
+    // The pair only accounts for the tokens it holds when the mint is executed.
+    match &token_in {
+        TokenType::CustomToken {
+            contract_addr,
+            token_code_hash,
+        } => {
+            response = response.add_message(snip20::transfer_msg(
+                pair.address.to_string(),
+                amount_in,
+                None,
+                None,
+                32,
+                token_code_hash.to_string(),
+                contract_addr.to_string(),
+            )?);
+        }
+        TokenType::NativeToken { denom } => {
+            if let WasmMsg::Execute { funds, .. } = &mut mint_msg {
+                *funds = vec![Coin {
+                    denom: denom.clone(),
+                    amount: amount_in,
+                }];
+            }
+        }
+    }
+
+    // NOTE: See reply in contract.rs for continuation of this function.
+
+    EPHEMERAL_ORDER.save(
+        deps.storage,
+        &Order {
+            order_id: 0,
+            owner,
+            lb_pair: pair.0,
+            order_type,
+            id: params.id,
+            token_in,
+            amount_in,
+            shares: Uint256::zero(),
+            tip: params.tip,
+        },
+    )?;
+
+    Ok(response.add_submessage(SubMsg::reply_on_success(mint_msg, MINT_REPLY_ID)))
+}
+
+/// Burns the liquidity of the filled orders `order_ids` to their owners, and pays their tips to
+/// the sender.
+pub fn execute_orders(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    order_ids: Vec<u64>,
+) -> Result<Response> {
+    let mut response = Response::new();
+
+    for order_id in order_ids {
+        let order = _load_order(deps.storage, order_id)?;
+
+        if !is_filled(deps.querier, &order)? {
+            return Err(Error::OrderNotFilled { order_id });
+        }
+
+        response = response
+            .add_message(_close_order(deps.storage, &env, &order)?)
+            .add_attribute("executed_order_id", order_id.to_string());
+
+        if let Some(tip) = order.token_in.transfer(order.tip, info.sender.clone()) {
+            response = response.add_message(tip);
+        }
+    }
+
+    Ok(response)
+}
+
+/// Burns the liquidity of the order `order_id` to its owner, and refunds the tip.
+/// The owner receives whatever the bin holds for the order, so it may be partially filled.
+pub fn cancel_order(deps: DepsMut, env: Env, info: MessageInfo, order_id: u64) -> Result<Response> {
+    let order = _load_order(deps.storage, order_id)?;
+
+    if order.owner != info.sender {
+        return Err(Error::OnlyOrderOwner { order_id });
+    }
+
+    let mut response = Response::new()
+        .add_message(_close_order(deps.storage, &env, &order)?)
+        .add_attribute("cancelled_order_id", order_id.to_string());
+
+    if let Some(refund) = order.token_in.transfer(order.tip, order.owner) {
+        response = response.add_message(refund);
+    }
+
+    Ok(response)
+}
+
+/// Creates a new viewing key for the caller, used to query its orders.
+pub fn create_viewing_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entropy: String,
+) -> Result<Response> {
+    let key = ViewingKey::create(
+        deps.storage,
+        &info,
+        &env,
+        info.sender.as_str(),
+        entropy.as_ref(),
+    );
+
+    Ok(Response::new().set_data(to_binary(&CreateViewingKeyResponse { key })?))
+}
+
+/// Sets the viewing key of the caller.
+pub fn set_viewing_key(deps: DepsMut, info: MessageInfo, key: String) -> Result<Response> {
+    ViewingKey::set(deps.storage, info.sender.as_str(), key.as_str());
+
+    Ok(Response::new())
+}
+
+/// Revokes a query permit previously signed by the caller.
+pub fn revoke_permit(deps: DepsMut, info: MessageInfo, permit_name: String) -> Result<Response> {
+    RevokedPermits::revoke_permit(
+        deps.storage,
+        PREFIX_REVOKED_PERMITS,
+        info.sender.as_str(),
+        &permit_name,
+    );
+
+    Ok(Response::new())
+}
+
+/// Returns true if the active id of the pair has moved past the bin of the order.
+pub fn is_filled(querier: QuerierWrapper, order: &Order) -> StdResult<bool> {
+    let active_id = ILbPair(order.lb_pair.clone()).get_active_id(querier)?;
+
+    Ok(match order.order_type {
+        OrderType::Ask => active_id > order.id,
+        OrderType::Bid => active_id < order.id,
+    })
+}
+
+fn _load_order(storage: &dyn Storage, order_id: u64) -> Result<Order> {
+    ORDERS
+        .get(storage, &order_id)
+        .ok_or(Error::OrderNotFound { order_id })
+}
+
+/// Removes the order from the order book and returns the message burning its liquidity to the
+/// owner.
+fn _close_order(storage: &mut dyn Storage, env: &Env, order: &Order) -> Result<CosmosMsg> {
+    ORDERS.remove(storage, &order.order_id)?;
+    USER_ORDERS
+        .add_suffix(order.owner.as_bytes())
+        .remove(storage, &order.order_id)?;
+
+    let burn_msg = ILbPair(order.lb_pair.clone()).burn(
+        env.contract.address.to_string(),
+        order.owner.to_string(),
+        vec![order.id],
+        vec![order.shares],
+    )?;
+
+    Ok(burn_msg.into())
+}
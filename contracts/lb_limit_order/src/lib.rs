@@ -0,0 +1,18 @@
+//! # Liquidity Book Limit Orders
+//!
+//! Places limit orders as single-sided liquidity in a single bin of an LB pair.
+//! An order is filled once the active id has moved past its bin, at which point anyone can burn
+//! its liquidity to the owner in exchange for the tip attached to the order.
+
+mod contract;
+mod execute;
+mod query;
+mod state;
+
+pub use contract::{execute, instantiate, migrate, query, reply};
+
+// Use this crate's custom Error type
+pub use liquidity_book::interfaces::lb_limit_order::LbLimitOrderError as Error;
+
+/// Alias for Result<T, LbLimitOrderError>
+pub type Result<T, E = Error> = core::result::Result<T, E>;
@@ -0,0 +1,78 @@
+use crate::{execute::is_filled, state::*, Error, Result};
+use cosmwasm_std::{Addr, Deps, Env, StdError, StdResult};
+use liquidity_book::interfaces::lb_limit_order::{
+    FactoryResponse, OrderResponse, UserOrdersResponse,
+};
+use secret_toolkit::{
+    permit::{validate, Permit, TokenPermissions},
+    viewing_key::{ViewingKey, ViewingKeyStore},
+};
+
+/// Checks that `key` is the viewing key of `account`, and returns the validated account.
+pub fn check_viewing_key(deps: Deps, account: &str, key: &str) -> Result<Addr> {
+    let account = deps.api.addr_validate(account)?;
+
+    ViewingKey::check(deps.storage, account.as_str(), key).map_err(|_| Error::Unauthorized)?;
+
+    Ok(account)
+}
+
+/// Validates a query permit signed for this contract, and returns the account that signed it.
+pub fn validate_permit(deps: Deps, env: &Env, permit: &Permit) -> Result<Addr> {
+    let account = validate(
+        deps,
+        PREFIX_REVOKED_PERMITS,
+        permit,
+        env.contract.address.to_string(),
+        None,
+    )?;
+
+    if !permit.check_permission(&TokenPermissions::Owner) {
+        return Err(Error::OwnerPermitRequired(format!(
+            "{:?}",
+            permit.params.permissions
+        )));
+    }
+
+    Ok(deps.api.addr_validate(&account)?)
+}
+
+/// Returns the LB factory used to find the pairs.
+pub fn get_factory(deps: Deps) -> StdResult<FactoryResponse> {
+    let factory = FACTORY.load(deps.storage)?.0;
+
+    Ok(FactoryResponse { factory })
+}
+
+/// Returns the order `order_id` of `owner`, and whether it is filled.
+///
+/// The orders of other accounts are reported as not found, so their existence isn't revealed.
+pub fn get_order(deps: Deps, owner: &Addr, order_id: u64) -> StdResult<OrderResponse> {
+    let order = ORDERS
+        .get(deps.storage, &order_id)
+        .filter(|order| order.owner == *owner)
+        .ok_or_else(|| StdError::not_found(format!("order {order_id}")))?;
+    let is_filled = is_filled(deps.querier, &order)?;
+
+    Ok(OrderResponse { order, is_filled })
+}
+
+/// Returns a page of the open orders of `owner`.
+pub fn get_user_orders(
+    deps: Deps,
+    owner: &Addr,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> StdResult<UserOrdersResponse> {
+    let user_orders = USER_ORDERS.add_suffix(owner.as_bytes());
+
+    let order_ids = user_orders.paging(deps.storage, page.unwrap_or(0), page_size.unwrap_or(10))?;
+    let total = user_orders.get_len(deps.storage)?;
+
+    let orders = order_ids
+        .into_iter()
+        .map(|order_id| get_order(deps, owner, order_id))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(UserOrdersResponse { orders, total })
+}
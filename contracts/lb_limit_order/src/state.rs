@@ -0,0 +1,17 @@
+use liquidity_book::interfaces::{lb_factory::ILbFactory, lb_limit_order::Order};
+use secret_toolkit::{
+    serialization::Json,
+    storage::{Item, Keymap, Keyset},
+};
+
+pub const PREFIX_REVOKED_PERMITS: &str = "revoked_permits";
+
+pub const FACTORY: Item<ILbFactory> = Item::new(b"factory");
+pub const NEXT_ORDER_ID: Item<u64> = Item::new(b"next_order_id");
+
+pub const ORDERS: Keymap<u64, Order, Json> = Keymap::new(b"orders");
+/// The ids of the open orders of each user, suffixed by the user address.
+pub const USER_ORDERS: Keyset<u64> = Keyset::new(b"user_orders");
+
+/// The order being placed, waiting for the pair's mint response to know its shares.
+pub const EPHEMERAL_ORDER: Item<Order, Json> = Item::new(b"ephemeral_order");
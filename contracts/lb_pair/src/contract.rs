@@ -2,8 +2,9 @@ use crate::{
     execute::*,
     helper::*,
     lb_token::{
-        approve_for_all, balance_of, balance_of_batch, is_approved_for_all, name, symbol,
-        total_supply, TOTAL_SUPPLY,
+        allowance, approve, approve_for_all, balance_of, balance_of_batch, check_viewing_key,
+        create_viewing_key, is_approved_for_all, name, revoke_permit, set_viewing_key, symbol,
+        total_supply, validate_permit, TOTAL_SUPPLY,
     },
     query::*,
     state::*,
@@ -11,31 +12,42 @@ use crate::{
 };
 use cosmwasm_std::{
     entry_point, from_binary, to_binary, Addr, Binary, ContractInfo, CosmosMsg, Deps, DepsMut, Env,
-    Event, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, SubMsgResult, Uint128,
-    Uint256, WasmMsg,
+    Event, MessageInfo, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult,
+    Uint128, Uint256, WasmMsg,
 };
 use liquidity_book::{
-    core::TokenType,
-    interfaces::{lb_pair::*, lb_token, lb_token::state_structs::LbPair},
-    libraries::{constants, BinHelper, Bytes32, PackedUint128Math, PairParameters},
+    core::{
+        contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+        TokenType,
+    },
+    interfaces::{lb_pair::*, lb_token, lb_token::state_structs::LbPair, lb_token2},
+    libraries::{constants, hooks, BinHelper, Bytes32, PackedUint128Math, PairParameters},
+};
+use secret_toolkit::{
+    crypto::sha_256,
+    permit::Permit,
+    viewing_key::{ViewingKey, ViewingKeyStore},
 };
-// TODO: get rid of admin stuff and shade_protocol dependency
-use shade_protocol::admin::helpers::{validate_admin, AdminPermissions};
 
 pub const INSTANTIATE_LB_TOKEN_REPLY_ID: u64 = 1u64;
 pub const FLASH_LOAN_REPLY_ID: u64 = 999u64;
 
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    info: MessageInfo,
+    _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response> {
     const LB_TOKEN_DECIMALS: u8 = 18;
     // TODO: isn't this supposed to start at 0?
     // const START_ORACLE_ID: u16 = 1;
 
+    ViewingKey::set_seed(deps.storage, &sha_256(msg.entropy.as_bytes()));
+
     // Initializing the Token Contract
     let token_x_symbol = match msg.token_x.clone() {
         TokenType::CustomToken {
@@ -111,15 +123,9 @@ pub fn instantiate(
         }
     }
 
-    let state = State {
-        creator: info.sender,
-        // viewing_key,
-        admin_auth: msg.admin_auth.validate(deps.api)?,
-    };
-
-    // TODO: rename?
-    STATE.save(deps.storage, &state)?;
     CONTRACT_STATUS.save(deps.storage, &ContractStatus::Active)?;
+    PAUSED_OPERATIONS.save(deps.storage, &0)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     VIEWING_KEY.save(deps.storage, &viewing_key)?;
 
     TOKEN_X.save(deps.storage, &msg.token_x)?;
@@ -161,28 +167,18 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response> {
-    match CONTRACT_STATUS.load(deps.storage)? {
-        ContractStatus::FreezeAll => match msg {
-            ExecuteMsg::Mint { .. }
-            | ExecuteMsg::Swap { .. }
-            | ExecuteMsg::Burn { .. }
-            // | ExecuteMsg::Receive(..) 
-            => {
-                return Err(Error::TransactionBlock());
-            }
-            _ => {}
-        },
-        ContractStatus::LpWithdrawOnly => match msg {
-            ExecuteMsg::Mint { .. } | ExecuteMsg::Swap { .. } => {
-                return Err(Error::TransactionBlock());
-            }
-            _ => {}
-        },
-        ContractStatus::Active => {}
+    if let ExecuteMsg::Receive(msg) = msg {
+        return receive(deps, env, info, msg);
     }
 
+    check_execute_allowed(deps.storage, Operation::from(&msg))?;
+
     match msg {
-        ExecuteMsg::Swap { swap_for_y, to } => swap(deps, env, info, swap_for_y, to),
+        ExecuteMsg::Swap {
+            swap_for_y,
+            to,
+            id_limit,
+        } => swap(deps, env, info, swap_for_y, to, id_limit),
         ExecuteMsg::FlashLoan {
             receiver,
             amounts,
@@ -243,58 +239,157 @@ pub fn execute(
         ExecuteMsg::ApproveForAll { spender, approved } => {
             approve_for_all(deps, env, info, spender, approved).map_err(Error::from)
         }
+        ExecuteMsg::Approve {
+            spender,
+            id,
+            amount,
+            expiration,
+        } => approve(deps, env, info, spender, id, amount, expiration).map_err(Error::from),
 
         // not in joe-v2
         ExecuteMsg::SetContractStatus { contract_status } => {
-            let state = STATE.load(deps.storage)?;
-            validate_admin(
-                &deps.querier,
-                AdminPermissions::ShadeSwapAdmin,
-                &info.sender,
-                &state.admin_auth.into(),
-            )?;
+            only_factory_or_owner(deps.as_ref(), &info.sender)?;
             CONTRACT_STATUS.save(deps.storage, &contract_status)?;
 
             Ok(Response::default().add_attribute("new_status", contract_status.to_string()))
-        } // ExecuteMsg::Receive(msg) => {
-          //     let checked_addr = deps.api.addr_validate(&msg.from)?;
-          //     receiver_callback(deps, env, info, checked_addr, msg.amount, msg.msg)
-          // }
+        }
+        ExecuteMsg::CreateViewingKey { entropy } => {
+            create_viewing_key(deps, env, info, entropy).map_err(Error::from)
+        }
+        ExecuteMsg::SetViewingKey { key } => {
+            set_viewing_key(deps, env, info, key).map_err(Error::from)
+        }
+        ExecuteMsg::RevokePermit { permit_name } => {
+            revoke_permit(deps, env, info, permit_name).map_err(Error::from)
+        }
+        ExecuteMsg::SetPausedOperations { operations, paused } => {
+            set_paused_operations(deps, env, info, operations, paused)
+        }
+        ExecuteMsg::WithdrawPendingMint {} => withdraw_pending_mint(deps, env, info),
+        ExecuteMsg::Receive(..) => unreachable!("handled before the checks"),
     }
 }
 
-// TODO: I think we should remove this! The swap function should always be called by the lb-router.
-// Users send tokens to the router, then it transfer tokens to the lb-pair.
-
-// pub fn receiver_callback(
-//     deps: DepsMut,
-//     env: Env,
-//     info: MessageInfo,
-//     _from: Addr,
-//     _amount: Uint128,
-//     msg: Option<Binary>,
-// ) -> Result<Response> {
-//     let msg = msg.ok_or(Error::ReceiverMsgEmpty)?;
-//
-//     let response = match from_binary(&msg)? {
-//         InvokeMsg::Swap { swap_for_y, to } => {
-//             // this check needs to be here instead of in execute() because it is impossible to (cleanly) distinguish between swaps and lp withdraws until this point
-//             // if contract_status is FreezeAll, this fn will never be called, so only need to check LpWithdrawOnly here
-//             if CONTRACT_STATUS.load(deps.storage)? == ContractStatus::LpWithdrawOnly {
-//                 return Err(Error::TransactionBlock());
-//             }
-//
-//             if info.sender != TOKEN_X.load(deps.storage)?.unique_key()
-//                 && info.sender != TOKEN_Y.load(deps.storage)?.unique_key()
-//             {
-//                 return Err(Error::NoMatchingTokenInPair);
-//             }
-//
-//             swap(deps, env, info, swap_for_y, to)?
-//         }
-//     };
-//     Ok(response)
-// }
+/// The kinds of operations that are restricted by the contract status, the paused operations or
+/// the reentrancy guard.
+#[derive(Clone, Copy)]
+enum Operation {
+    Swap,
+    Mint,
+    Burn,
+    FlashLoan,
+    Transfer,
+    CollectProtocolFees,
+    WithdrawPendingMint,
+    Other,
+}
+
+impl From<&ExecuteMsg> for Operation {
+    fn from(msg: &ExecuteMsg) -> Self {
+        match msg {
+            ExecuteMsg::Swap { .. } => Operation::Swap,
+            ExecuteMsg::Mint { .. } => Operation::Mint,
+            ExecuteMsg::Burn { .. } => Operation::Burn,
+            ExecuteMsg::FlashLoan { .. } => Operation::FlashLoan,
+            ExecuteMsg::BatchTransferFrom { .. } => Operation::Transfer,
+            ExecuteMsg::CollectProtocolFees {} => Operation::CollectProtocolFees,
+            ExecuteMsg::WithdrawPendingMint {} => Operation::WithdrawPendingMint,
+            _ => Operation::Other,
+        }
+    }
+}
+
+/// Checks that `operation` is allowed by the contract status and the paused operations, and guards
+/// the operations that move tokens against reentrancy.
+fn check_execute_allowed(storage: &dyn Storage, operation: Operation) -> Result<()> {
+    let blocked = match CONTRACT_STATUS.load(storage)? {
+        ContractStatus::FreezeAll => matches!(
+            operation,
+            Operation::Mint
+                | Operation::Swap
+                | Operation::Burn
+                | Operation::FlashLoan
+                | Operation::WithdrawPendingMint
+        ),
+        ContractStatus::LpWithdrawOnly => matches!(
+            operation,
+            Operation::Mint | Operation::Swap | Operation::FlashLoan
+        ),
+        ContractStatus::Active => false,
+    };
+    if blocked {
+        return Err(Error::TransactionBlock());
+    }
+
+    let pause_flag = match operation {
+        Operation::Swap => PAUSE_SWAP,
+        Operation::Mint => PAUSE_MINT,
+        Operation::Burn => PAUSE_BURN,
+        Operation::FlashLoan => PAUSE_FLASH_LOAN,
+        Operation::Transfer => PAUSE_TRANSFER,
+        Operation::CollectProtocolFees => PAUSE_COLLECT_PROTOCOL_FEES,
+        Operation::WithdrawPendingMint | Operation::Other => 0,
+    };
+    if PAUSED_OPERATIONS.load(storage)? & pause_flag != 0 {
+        return Err(Error::OperationPaused);
+    }
+
+    if !matches!(operation, Operation::Transfer | Operation::Other) {
+        non_reentrant(storage)?;
+    }
+
+    Ok(())
+}
+
+/// Handles the tokens sent through a SNIP-20 `Send`, as if the `from` account had transferred
+/// them to the pair before calling it.
+fn receive(
+    deps: DepsMut,
+    env: Env,
+    mut info: MessageInfo,
+    receive_msg: Snip20ReceiveMsg,
+) -> Result<Response> {
+    let msg = receive_msg.msg.ok_or(Error::ReceiverMsgEmpty)?;
+    let from = deps.api.addr_validate(&receive_msg.from)?;
+
+    // The sender of a SNIP-20 receive message is the token contract.
+    let token = std::mem::replace(&mut info.sender, from);
+
+    match from_binary(&msg)? {
+        InvokeMsg::Swap {
+            swap_for_y,
+            to,
+            id_limit,
+        } => execute(
+            deps,
+            env,
+            info,
+            ExecuteMsg::Swap {
+                swap_for_y,
+                to,
+                id_limit,
+            },
+        ),
+        InvokeMsg::Mint {
+            to,
+            liquidity_configs,
+            refund_to,
+        } => {
+            check_execute_allowed(deps.storage, Operation::Mint)?;
+
+            receive_mint(
+                deps,
+                env,
+                info,
+                token,
+                receive_msg.amount,
+                to,
+                liquidity_configs,
+                refund_to,
+            )
+        }
+    }
+}
 
 #[entry_point]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
@@ -322,23 +417,44 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
         QueryMsg::GetSwapIn {
             amount_out,
             swap_for_y,
-        } => to_binary(&get_swap_in(deps, env, amount_out.u128(), swap_for_y)?),
+            id_limit,
+            at_timestamp,
+            volatility_accumulator,
+        } => to_binary(&get_swap_in(
+            deps,
+            env,
+            amount_out.u128(),
+            swap_for_y,
+            id_limit,
+            at_timestamp,
+            volatility_accumulator,
+        )?),
         QueryMsg::GetSwapOut {
             amount_in,
             swap_for_y,
-        } => to_binary(&get_swap_out(deps, env, amount_in.u128(), swap_for_y)?),
+            id_limit,
+            at_timestamp,
+            volatility_accumulator,
+        } => to_binary(&get_swap_out(
+            deps,
+            env,
+            amount_in.u128(),
+            swap_for_y,
+            id_limit,
+            at_timestamp,
+            volatility_accumulator,
+        )?),
 
         // lb-token
         QueryMsg::Name {} => to_binary(&name()),
         QueryMsg::Symbol {} => to_binary(&symbol()),
         QueryMsg::TotalSupply { id } => to_binary(&total_supply(deps, id)),
-        QueryMsg::BalanceOf { account, id } => to_binary(&balance_of(deps, account, id)),
-        QueryMsg::BalanceOfBatch { accounts, ids } => {
-            to_binary(&balance_of_batch(deps, accounts, ids)?)
-        }
-        QueryMsg::IsApprovedForAll { owner, spender } => {
-            to_binary(&is_approved_for_all(deps, owner, spender))
-        }
+        QueryMsg::BalanceOf { .. }
+        | QueryMsg::BalanceOfBatch { .. }
+        | QueryMsg::IsApprovedForAll { .. }
+        | QueryMsg::GetUserBins { .. }
+        | QueryMsg::GetAccruedFees { .. }
+        | QueryMsg::GetAllowance { .. } => return viewing_keys_queries(deps, env, msg),
 
         // not in joe-v2
         QueryMsg::GetLbToken {} => to_binary(&get_lb_token(deps)?), // TODO: delete
@@ -349,10 +465,135 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
             page,
             page_size,
         } => to_binary(&get_all_bins(deps, env, page, page_size, id)?),
+        QueryMsg::GetBinsInRange {
+            start_id,
+            end_id,
+            direction,
+            limit,
+        } => to_binary(&get_bins_in_range(
+            deps, start_id, end_id, direction, limit,
+        )?),
+        QueryMsg::GetLiquidityDepth { radius } => to_binary(&get_liquidity_depth(deps, radius)?),
+        QueryMsg::GetPausedOperations {} => to_binary(&get_paused_operations(deps)?),
+        QueryMsg::GetContractStatus {} => to_binary(&ContractStatusResponse {
+            contract_status: CONTRACT_STATUS.load(deps.storage)?,
+        }),
+        QueryMsg::GetOracleSamplesAt { lookup_timestamps } => {
+            to_binary(&get_oracle_samples_at(deps, env, lookup_timestamps)?)
+        }
+        QueryMsg::GetTwap {
+            seconds_ago_start,
+            seconds_ago_end,
+        } => to_binary(&get_twap(deps, env, seconds_ago_start, seconds_ago_end)?),
+        QueryMsg::WithPermit { permit, query } => return permit_queries(deps, env, permit, query),
+    }
+    .map_err(Error::StdError)
+}
+
+fn viewing_keys_queries(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary> {
+    match msg {
+        QueryMsg::BalanceOf { account, key, id } => {
+            check_viewing_key(deps, &account, &key)?;
+            to_binary(&balance_of(deps, account, id))
+        }
+        QueryMsg::BalanceOfBatch { accounts, key, ids } => {
+            for account in &accounts {
+                check_viewing_key(deps, account, &key)?;
+            }
+            to_binary(&balance_of_batch(deps, accounts, ids)?)
+        }
+        QueryMsg::IsApprovedForAll {
+            owner,
+            spender,
+            key,
+        } => {
+            check_viewing_key(deps, &owner, &key)
+                .or_else(|_| check_viewing_key(deps, &spender, &key))?;
+            to_binary(&is_approved_for_all(deps, owner, spender))
+        }
+        QueryMsg::GetUserBins {
+            account,
+            key,
+            page,
+            page_size,
+        } => {
+            check_viewing_key(deps, &account, &key)?;
+            to_binary(&get_user_bins(deps, account, page, page_size)?)
+        }
+        QueryMsg::GetAccruedFees {
+            account,
+            key,
+            ids,
+            page,
+            page_size,
+        } => {
+            check_viewing_key(deps, &account, &key)?;
+            to_binary(&get_accrued_fees(deps, account, ids, page, page_size)?)
+        }
+        QueryMsg::GetAllowance {
+            owner,
+            spender,
+            id,
+            key,
+        } => {
+            check_viewing_key(deps, &owner, &key)
+                .or_else(|_| check_viewing_key(deps, &spender, &key))?;
+            to_binary(&allowance(deps, &env, owner, spender, id))
+        }
+        _ => unreachable!("This query type does not require viewing key authentication"),
+    }
+    .map_err(Error::StdError)
+}
+
+fn permit_queries(deps: Deps, env: Env, permit: Permit, query: QueryWithPermit) -> Result<Binary> {
+    let account = validate_permit(deps, &env, &permit)?;
+
+    match query {
+        QueryWithPermit::BalanceOf { id } => to_binary(&balance_of(deps, account, id)),
+        QueryWithPermit::BalanceOfBatch { ids } => {
+            let accounts = vec![account; ids.len()];
+            to_binary(&balance_of_batch(deps, accounts, ids)?)
+        }
+        QueryWithPermit::IsApprovedForAll { owner, spender } => {
+            if account != owner && account != spender {
+                return Err(lb_token2::LbTokenError::Unauthorized.into());
+            }
+            to_binary(&is_approved_for_all(deps, owner, spender))
+        }
+        QueryWithPermit::GetUserBins { page, page_size } => {
+            to_binary(&get_user_bins(deps, account, page, page_size)?)
+        }
+        QueryWithPermit::GetAccruedFees {
+            ids,
+            page,
+            page_size,
+        } => to_binary(&get_accrued_fees(deps, account, ids, page, page_size)?),
+        QueryWithPermit::GetAllowance { owner, spender, id } => {
+            if account != owner && account != spender {
+                return Err(lb_token2::LbTokenError::Unauthorized.into());
+            }
+            to_binary(&allowance(deps, &env, owner, spender, id))
+        }
     }
     .map_err(Error::StdError)
 }
 
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Pairs instantiated before operations could be paused have no paused operations stored.
+    if PAUSED_OPERATIONS.may_load(deps.storage)?.is_none() {
+        PAUSED_OPERATIONS.save(deps.storage, &0)?;
+    }
+
+    // NOTE: The bins of each account and the fee checkpoints are not backfilled, because the
+    // balances are stored per account and the accounts can't be listed. They are recorded at the
+    // next balance change of each account, and a balance without a checkpoint accrues no fees.
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
     match (msg.id, msg.result) {
@@ -407,9 +648,10 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
                     viewing_key.to_string(),
                 )?;
 
-                // NOTE: This is written to match the original, but in our case it only encodes the token balances.
-                let balances_after =
-                    [0u8; 32].received(token_x_balance.u128(), token_y_balance.u128());
+                // NOTE: This is written to match the original. The tokens held for pending mints
+                // are not part of the balances available to the pair.
+                let balances_after = pending_mints_total(deps.storage)?
+                    .received(token_x_balance.u128(), token_y_balance.u128());
 
                 let EphemeralFlashLoan {
                     reserves_before,
@@ -433,7 +675,10 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
                         .map_err(|e| StdError::GenericErr { msg: e.to_string() })
                 })?;
 
+                EPHEMERAL_FLASH_LOAN.remove(deps.storage);
+
                 let parameters = PARAMETERS.load(deps.storage)?;
+                let hooks_parameters = HOOKS_PARAMETERS.load(deps.storage)?;
 
                 let event = Event::flash_loan(
                     &sender,
@@ -444,10 +689,17 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
                     &fees_received,
                 );
 
-                // TODO: Hooks
-                //     Hooks.afterFlashLoan(hooksParameters, msg.sender, address(receiver), totalFees, feesReceived);
+                let mut response = Response::new().add_event(event);
 
-                let response = Response::new().add_event(event);
+                if let Some(after_flash_loan_hook) = hooks::after_flash_loan(
+                    hooks_parameters,
+                    &sender,
+                    &receiver,
+                    total_fees,
+                    fees_received,
+                )? {
+                    response = response.add_message(after_flash_loan_hook)
+                }
 
                 Ok(response)
             }
@@ -456,3 +708,50 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
         _ => Err(Error::UnknownReplyId { id: msg.id }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lb_token::{accrued_fees, user_bins, BALANCES, TOTAL_SUPPLIES};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+
+    #[test]
+    fn test_migrate_pre_series_pair() {
+        let mut deps = mock_dependencies();
+        let id = 8388608u32;
+        let supply = Uint256::from(1_000_000u128);
+
+        // A pair instantiated before versions, paused operations, user bins and fee checkpoints
+        // were stored.
+        TOTAL_SUPPLIES
+            .insert(deps.as_mut().storage, &id, &supply)
+            .unwrap();
+        BALANCES
+            .add_suffix(b"alice")
+            .insert(deps.as_mut().storage, &id, &supply)
+            .unwrap();
+        FEES_PER_SHARE
+            .insert(
+                deps.as_mut().storage,
+                &id,
+                &FeesPerShare {
+                    x: Uint256::from(u128::MAX),
+                    y: Uint256::zero(),
+                },
+            )
+            .unwrap();
+
+        let response = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        assert_eq!(response.attributes[0].value, "none");
+        assert_eq!(PAUSED_OPERATIONS.load(deps.as_ref().storage).unwrap(), 0);
+        assert_eq!(
+            user_bins(deps.as_ref(), "alice", 0, 10).unwrap(),
+            (vec![], 0)
+        );
+        assert_eq!(
+            accrued_fees(deps.as_ref().storage, "alice", id).unwrap(),
+            (0, 0)
+        );
+    }
+}
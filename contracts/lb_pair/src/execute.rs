@@ -1,7 +1,7 @@
 use crate::{
     contract::FLASH_LOAN_REPLY_ID,
     helper::*,
-    lb_token::{_burn, _mint, total_supply},
+    lb_token::{_burn, _is_approved_for_all, _mint, _spend_allowances, total_supply},
     state::*,
     Error, Result,
 };
@@ -53,14 +53,19 @@ pub struct MintArrays {
 /// transferred to this contract through another contract, most likely the router.
 /// That is why this function shouldn't be called directly, but only through one of the swap functions of a router
 /// that will also perform safety checks, such as minimum amounts and slippage.
+/// Native tokens are expected to be sent as funds along with this message.
 ///
 /// The variable fee is updated throughout the swap, it increases with the number of bins crossed.
 /// The oracle is updated at the end of the swap.
 ///
+/// If `id_limit` is set, the swap stops at that bin, and the unused input is refunded to the
+/// sender. The swap fails if the active id is already past `id_limit`.
+///
 /// # Arguments
 ///
 /// * `swap_for_y` - Whether you're swapping token X for token Y (true) or token Y for token X (false)
 /// * `to` - The address to send the tokens to
+/// * `id_limit` - The id of the last bin the swap may use
 ///
 /// # Returns
 ///
@@ -71,6 +76,7 @@ pub fn swap(
     info: MessageInfo,
     swap_for_y: bool,
     to: String,
+    id_limit: Option<u32>,
 ) -> Result<Response> {
     let mut response = Response::new();
 
@@ -87,6 +93,8 @@ pub fn swap(
     let token_y = TOKEN_Y.load(deps.storage)?;
     let viewing_key = VIEWING_KEY.load(deps.storage)?;
 
+    check_native_funds(&info, &token_x, &token_y)?;
+
     // NOTE: These balance queries should never fail.
     // A few different options here...
 
@@ -193,10 +201,12 @@ pub fn swap(
     //     _ => panic!("idk lol"),
     // };
 
+    let held = reserves.add(pending_mints_total(deps.storage)?)?;
+
     let mut amounts_left = if swap_for_y {
-        reserves.received_x(token_x_balance.u128())
+        held.received_x(token_x_balance.u128())
     } else {
-        reserves.received_y(token_y_balance.u128())
+        held.received_y(token_y_balance.u128())
     };
 
     if amounts_left == [0; 32] {
@@ -220,6 +230,13 @@ pub fn swap(
 
     let mut active_id = parameters.get_active_id();
 
+    if is_past_id_limit(swap_for_y, active_id, id_limit) {
+        return Err(Error::InvalidIdLimit {
+            id_limit: id_limit.unwrap_or_default(),
+            active_id,
+        });
+    }
+
     parameters.update_references(env.block.time.seconds())?;
 
     let mut events: Vec<Event> = Vec::new();
@@ -245,6 +262,8 @@ pub fn swap(
                     amounts_in_with_fees = amounts_in_with_fees.sub(p_fees)?;
                 }
 
+                accrue_fees_per_share(deps.storage, active_id, total_fees.sub(p_fees)?)?;
+
                 BINS.insert(
                     deps.storage,
                     &active_id,
@@ -261,7 +280,7 @@ pub fn swap(
                     amounts_out_of_bin,
                     parameters.get_volatility_accumulator(),
                     total_fees,
-                    protocol_fees,
+                    p_fees,
                 ));
             }
         }
@@ -272,8 +291,15 @@ pub fn swap(
             let next_id = _get_next_non_empty_bin(deps.as_ref(), swap_for_y, active_id);
 
             if next_id == 0 || next_id == (U24::MAX) {
+                // With an id limit, running out of liquidity is just a partial fill.
+                if id_limit.is_some() {
+                    break;
+                }
                 return Err(Error::OutOfLiquidity);
             }
+            if is_past_id_limit(swap_for_y, next_id, id_limit) {
+                break;
+            }
             active_id = next_id;
         }
     }
@@ -282,7 +308,8 @@ pub fn swap(
         return Err(Error::InsufficientAmountOut);
     }
 
-    RESERVES.save(deps.storage, &reserves.sub(amounts_out)?)?;
+    // Anything left was stopped by the id limit, and is refunded.
+    RESERVES.save(deps.storage, &reserves.sub(amounts_out)?.sub(amounts_left)?)?;
     PROTOCOL_FEES.save(deps.storage, &protocol_fees)?;
 
     // TODO: this part is untested
@@ -303,6 +330,12 @@ pub fn swap(
     }
     .expect("there must be a transfer message");
 
+    let refund_msg = if swap_for_y {
+        bin_transfer_x(amounts_left, token_x.clone(), info.sender.clone())
+    } else {
+        bin_transfer_y(amounts_left, token_y.clone(), info.sender.clone())
+    };
+
     let data = lb_pair::SwapResponse { amounts_out };
 
     response = response
@@ -310,6 +343,7 @@ pub fn swap(
         // TODO: see if this works instead; skip the serialization
         .set_data(Binary::from(amounts_out))
         .add_message(transfer_msg)
+        .add_messages(refund_msg)
         .add_events(events);
 
     if let Some(after_swap_hook) =
@@ -325,6 +359,10 @@ pub fn swap(
 /// The receiver contract is expected to return the tokens plus a fee to this contract.
 /// The fee is calculated as a percentage of the amount borrowed, and is the same for both tokens.
 ///
+/// The callback is dispatched as a submessage, and the balances of the pair are verified in the
+/// reply. The receiver must return the tokens with a SNIP-20 `Transfer` (not `Send`), or a
+/// `BankMsg::Send` for native tokens, and set `CALLBACK_SUCCESS` as the response data.
+///
 /// # Arguments
 ///
 /// * `receiver` - The contract that will receive the tokens and execute the callback function
@@ -342,24 +380,39 @@ pub fn flash_loan(
         return Err(Error::ZeroBorrowAmount);
     }
 
+    let receiver = ContractInfo {
+        address: deps.api.addr_validate(receiver.address.as_str())?,
+        code_hash: receiver.code_hash,
+    };
+
     let hooks_parameters = HOOKS_PARAMETERS.load(deps.storage)?;
 
     let reserves_before = RESERVES.load(deps.storage)?;
+
+    // The pair also holds the tokens of the pending mints, which are not part of the reserves.
+    if amounts.gt(reserves_before) {
+        return Err(Error::FlashLoanExceedsReserves);
+    }
+
     let total_fees = _get_flash_loan_fees(deps.as_ref(), amounts)?;
 
-    // TODO: Hooks
-    //     Hooks.beforeFlashLoan(hooksParameters, msg.sender, address(receiver), amounts);
+    let mut response = Response::new();
 
-    // TODO: This should return a WasmMsg that can be added as a "fire-and-forget" submessage.
-    // let hook_before =
-    // hooks::before_flash_loan(hooks_parameters, info.sender, receiver.address, amounts);
+    if let Some(before_flash_loan_hook) =
+        hooks::before_flash_loan(hooks_parameters, &info.sender, &receiver.address, amounts)?
+    {
+        response = response.add_message(before_flash_loan_hook)
+    }
 
-    // TODO: transfer the requested token amounts to the receiver
-    //     amounts.transfer(_tokenX(), _tokenY(), address(receiver));
+    let token_x = TOKEN_X.load(deps.storage)?;
+    let token_y = TOKEN_Y.load(deps.storage)?;
 
-    // TODO: how to handle the native token case?
-    let token_x = TOKEN_X.load(deps.storage)?.into_contract_info().unwrap();
-    let token_y = TOKEN_Y.load(deps.storage)?.into_contract_info().unwrap();
+    let transfer_messages = bin_transfer(
+        amounts,
+        token_x.clone(),
+        token_y.clone(),
+        receiver.address.clone(),
+    );
 
     let msg = lb_flash_loan_callback::ExecuteMsg::LbFlashLoanCallback {
         address: info.sender.to_string(),
@@ -392,11 +445,11 @@ pub fn flash_loan(
         },
     )?;
 
-    let response = Response::new().add_submessage(msg);
+    // The loaned amounts are sent before the callback is executed. The balances are verified
+    // in the reply, once the receiver has returned the tokens plus the fees.
+    response = response.add_messages(transfer_messages).add_submessage(msg);
 
     Ok(response)
-
-    // TODO: Handle the rest of this function in the Reply.
 }
 
 /// Returns the encoded fees amounts for a flash loan
@@ -440,6 +493,7 @@ pub fn _get_flash_loan_fees(deps: Deps, amounts: Bytes32) -> Result<Bytes32> {
 /// transferred to this contract through another contract, most likely the router.
 /// That is why this function shouldn't be called directly, but through one of the add liquidity functions of a
 /// router that will also perform safety checks.
+/// Native tokens are expected to be sent as funds along with this message.
 ///
 /// Any excess amount of token will be sent to the `refund_to` address.
 ///
@@ -470,12 +524,16 @@ pub fn mint(
     let to = deps.api.addr_validate(&to)?;
     let refund_to = deps.api.addr_validate(&refund_to)?;
 
+    let hooks_parameters = HOOKS_PARAMETERS.load(deps.storage)?;
+
     let reserves = RESERVES.load(deps.storage)?;
 
     let token_x = TOKEN_X.load(deps.storage)?;
     let token_y = TOKEN_Y.load(deps.storage)?;
     let viewing_key = VIEWING_KEY.load(deps.storage)?;
 
+    check_native_funds(&info, &token_x, &token_y)?;
+
     let token_x_balance = token_x.query_balance(
         deps.as_ref(),
         env.contract.address.to_string(),
@@ -487,11 +545,23 @@ pub fn mint(
         viewing_key.to_string(),
     )?;
 
-    let amounts_received = reserves.received(token_x_balance.u128(), token_y_balance.u128());
+    let amounts_received = reserves
+        .add(pending_mints_total(deps.storage)?)?
+        .received(token_x_balance.u128(), token_y_balance.u128());
 
     let mut messages: Vec<CosmosMsg> = Vec::new();
     let mut events: Vec<Event> = Vec::new();
 
+    if let Some(before_mint_hook) = hooks::before_mint(
+        hooks_parameters.clone(),
+        &info.sender,
+        &to,
+        &liquidity_configs,
+        amounts_received,
+    )? {
+        messages.push(before_mint_hook.into())
+    }
+
     // NOTE: Liquidity is a 128.128-binary fixed-point number. It is a function of price.
     let mut arrays = MintArrays {
         ids: vec![0u32; liquidity_configs.len()],
@@ -505,7 +575,7 @@ pub fn mint(
         &info,
         &mut messages,
         &mut events,
-        liquidity_configs,
+        &liquidity_configs,
         amounts_received,
         to.clone(),
         &mut arrays,
@@ -549,12 +619,22 @@ pub fn mint(
         liquidity_minted,
     };
 
-    let response = Response::new()
+    let mut response = Response::new()
         .set_data(to_binary(&data)?)
         .add_events(events)
         .add_messages(messages)
         .add_messages(refund_messages);
 
+    if let Some(after_mint_hook) = hooks::after_mint(
+        hooks_parameters,
+        &info.sender,
+        &to,
+        &liquidity_configs,
+        amounts_received.sub(amounts_left)?,
+    )? {
+        response = response.add_message(after_mint_hook)
+    }
+
     Ok(response)
 }
 
@@ -576,7 +656,7 @@ fn mint_bins(
     info: &MessageInfo,
     messages: &mut Vec<CosmosMsg>,
     events: &mut Vec<Event>,
-    liquidity_configs: Vec<LiquidityConfigurations>,
+    liquidity_configs: &[LiquidityConfigurations],
     amounts_received: Bytes32,
     to: Addr,
     arrays: &mut MintArrays,
@@ -700,6 +780,10 @@ fn update_bin(
                 })?;
             }
 
+            // The composition fees left in the bin are earned by the existing shares only, so
+            // they are accrued before the shares of the minter are computed and checkpointed.
+            accrue_fees_per_share(deps.storage, id, fees.sub(protocol_c_fees)?)?;
+
             let bin_liquidity = bin_reserves
                 .add(fees.sub(protocol_c_fees)?)?
                 .get_liquidity(price)?;
@@ -742,6 +826,8 @@ fn update_bin(
 /// Burn Liquidity Book (LB) tokens and withdraw tokens from the pool.
 ///
 /// This function will burn the tokens directly from the caller.
+/// The caller must be `from`, be approved for all of `from`'s tokens, or have enough allowance
+/// for each id, which is spent by the burn.
 ///
 /// # Arguments
 ///
@@ -755,7 +841,7 @@ fn update_bin(
 /// * `amounts` - The amounts of token X and token Y received by the user
 pub fn burn(
     deps: &mut DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     from: String,
     to: String,
@@ -766,13 +852,36 @@ pub fn burn(
         return Err(Error::InvalidInput);
     }
 
-    // bytes32 hooksParameters = _hooksParameters;
-    //
-    // Hooks.beforeBurn(hooksParameters, msg.sender, from, to, ids, amountsToBurn);
-
     let from = deps.api.addr_validate(&from)?;
     let to = deps.api.addr_validate(&to)?;
 
+    // Burning someone else's shares requires the same approval as transferring them.
+    if !_is_approved_for_all(deps.as_ref(), &from.to_string(), &info.sender.to_string()) {
+        _spend_allowances(
+            deps.storage,
+            &env.block,
+            &from,
+            &info.sender,
+            &ids,
+            &amounts_to_burn,
+        )?;
+    }
+
+    let hooks_parameters = HOOKS_PARAMETERS.load(deps.storage)?;
+
+    let mut response = Response::new();
+
+    if let Some(before_burn_hook) = hooks::before_burn(
+        hooks_parameters.clone(),
+        &info.sender,
+        &from,
+        &to,
+        &ids,
+        &amounts_to_burn,
+    )? {
+        response = response.add_message(before_burn_hook)
+    }
+
     let token_x = TOKEN_X.load(deps.storage)?;
     let token_y = TOKEN_Y.load(deps.storage)?;
 
@@ -869,11 +978,24 @@ pub fn burn(
 
     let response_data = to_binary(&BurnResponse { amounts })?;
 
-    Ok(Response::default()
+    response = response
         .set_data(response_data)
         // .add_message(burn_tokens_msg)
         .add_messages(transfer_messages)
-        .add_events(events))
+        .add_events(events);
+
+    if let Some(after_burn_hook) = hooks::after_burn(
+        hooks_parameters,
+        &info.sender,
+        &from,
+        &to,
+        &ids,
+        &amounts_to_burn,
+    )? {
+        response = response.add_message(after_burn_hook)
+    }
+
+    Ok(response)
 }
 
 /// Collect the protocol fees from the pool.
@@ -1093,7 +1215,35 @@ pub fn set_hooks_parameters(
     Ok(response)
 }
 
-// TODO:
+/// Pauses or unpauses operations of the pair, independently of each other.
+///
+/// Can only be called by the factory.
+///
+/// # Arguments
+///
+/// * `operations` - The bitmap of the operations to update (`PAUSE_SWAP`, `PAUSE_MINT`, ...)
+/// * `paused` - Whether these operations should be paused (true) or unpaused (false)
+pub fn set_paused_operations(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    operations: u8,
+    paused: bool,
+) -> Result<Response> {
+    only_factory(&info.sender, &FACTORY.load(deps.storage)?.address)?;
+
+    let paused_operations =
+        PAUSED_OPERATIONS.update(deps.storage, |paused_operations| -> StdResult<_> {
+            if paused {
+                Ok(paused_operations | operations)
+            } else {
+                Ok(paused_operations & !operations)
+            }
+        })?;
+
+    Ok(Response::new().add_attribute("paused_operations", paused_operations.to_string()))
+}
+
 /// Overrides the batch transfer function to call the hooks before and after the transfer
 pub fn batch_transfer_from(
     deps: DepsMut,
@@ -1122,17 +1272,18 @@ pub fn batch_transfer_from(
         response = response.add_message(before_batch_transfer_from_hook);
     }
 
-    // TODO: interesting... this is supposed to call the internal LBToken batch_transfer_from
-    // instead of adding a message to an external token contract.
-    // super::lb_token::batch_transfer_from(deps, env, info, from.to_string(), to.to_string(), ids, amounts)?;
-
-    let lb_token = LB_TOKEN.load(deps.storage)?;
-    // TODO: will this allow a "BatchTransferFrom" type of message?
-    let msg = lb_token::ExecuteMsg::BatchTransfer {
-        actions: todo!(),
-        padding: None,
-    }
-    .to_cosmos_msg(lb_token.code_hash, lb_token.address.to_string(), None)?;
+    // The LB token is embedded in this contract, so this updates the balances directly and emits
+    // the `transfer_batch` event.
+    let transfer_response = crate::lb_token::batch_transfer_from(
+        deps,
+        env,
+        info.clone(),
+        from.to_string(),
+        to.to_string(),
+        ids.clone(),
+        amounts.clone(),
+    )?;
+    response = response.add_events(transfer_response.events);
 
     if let Some(after_batch_transfer_from_hook) = hooks::after_transfer_from(
         hooks_parameters.clone(),
@@ -1145,7 +1296,126 @@ pub fn batch_transfer_from(
         response = response.add_message(after_batch_transfer_from_hook);
     }
 
-    Ok(response.add_message(msg))
+    Ok(response)
+}
+
+/// Mints liquidity with the tokens received through a SNIP-20 `Send`.
+///
+/// The deposits of an account are accumulated until every token used by the liquidity
+/// configurations has arrived, so both tokens can be sent in the same transaction. Every deposit
+/// of a pending mint must have the same `to`, `liquidity_configs` and `refund_to`. A single-sided
+/// mint is executed immediately. Pending deposits are tracked apart from the reserves and excluded
+/// from the received amounts, so that they can't be claimed by any other mint or swap.
+///
+/// # Arguments
+///
+/// * `token` - The address of the SNIP-20 token that was sent
+/// * `amount` - The amount of tokens that was sent
+/// * `to` - The address that will receive the LB tokens
+/// * `liquidity_configs` - The encoded liquidity configurations, each one containing the id of the
+///   bin and the percentage of token X and token Y to add to the bin.
+/// * `refund_to` - The address that will receive the excess amount of tokens
+#[allow(clippy::too_many_arguments)]
+pub fn receive_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: Addr,
+    amount: Uint128,
+    to: String,
+    liquidity_configs: Vec<LiquidityConfigurations>,
+    refund_to: String,
+) -> Result<Response> {
+    let token_x = TOKEN_X.load(deps.storage)?;
+    let token_y = TOKEN_Y.load(deps.storage)?;
+
+    let deposit = if token_x.is_custom_token() && token_x.address() == token {
+        Bytes32::encode_first(amount.u128())
+    } else if token_y.is_custom_token() && token_y.address() == token {
+        Bytes32::encode_second(amount.u128())
+    } else {
+        return Err(Error::NoMatchingTokenInPair {
+            token: token.to_string(),
+        });
+    };
+
+    let pending = match PENDING_MINTS.get(deps.storage, &info.sender) {
+        Some(pending_mint) => {
+            if pending_mint.to != to
+                || pending_mint.refund_to != refund_to
+                || pending_mint.liquidity_configs != liquidity_configs
+            {
+                return Err(Error::PendingMintMismatch {
+                    account: info.sender.to_string(),
+                });
+            }
+
+            pending_mint.amounts
+        }
+        None => [0u8; 32],
+    };
+    let (amount_x, amount_y) = pending.add(deposit)?.decode();
+
+    let (needs_x, needs_y) =
+        liquidity_configs
+            .iter()
+            .fold((false, false), |(needs_x, needs_y), config| {
+                let (distribution_x, distribution_y, _) =
+                    LiquidityConfigurations::decode_params(config.0);
+                (needs_x || distribution_x > 0, needs_y || distribution_y > 0)
+            });
+
+    if (needs_x && amount_x == 0) || (needs_y && amount_y == 0) {
+        PENDING_MINTS.insert(
+            deps.storage,
+            &info.sender,
+            &PendingMint {
+                amounts: pending.add(deposit)?,
+                to,
+                liquidity_configs,
+                refund_to,
+            },
+        )?;
+        PENDING_MINTS_TOTAL.save(
+            deps.storage,
+            &pending_mints_total(deps.storage)?.add(deposit)?,
+        )?;
+
+        return Ok(Response::new()
+            .add_attribute("pending_mint_x", amount_x.to_string())
+            .add_attribute("pending_mint_y", amount_y.to_string()));
+    }
+
+    // The pending deposits are released, so that the mint receives them along with the tokens
+    // that were just sent.
+    PENDING_MINTS.remove(deps.storage, &info.sender)?;
+    PENDING_MINTS_TOTAL.save(
+        deps.storage,
+        &pending_mints_total(deps.storage)?.sub(pending)?,
+    )?;
+
+    mint(deps, env, info, to, liquidity_configs, refund_to)
+}
+
+/// Refunds the tokens sent by the sender for a mint that is still waiting for its other token.
+pub fn withdraw_pending_mint(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response> {
+    let pending = PENDING_MINTS
+        .get(deps.storage, &info.sender)
+        .ok_or_else(|| Error::NoPendingMint {
+            account: info.sender.to_string(),
+        })?
+        .amounts;
+
+    PENDING_MINTS.remove(deps.storage, &info.sender)?;
+    PENDING_MINTS_TOTAL.save(
+        deps.storage,
+        &pending_mints_total(deps.storage)?.sub(pending)?,
+    )?;
+
+    let token_x = TOKEN_X.load(deps.storage)?;
+    let token_y = TOKEN_Y.load(deps.storage)?;
+
+    Ok(Response::new().add_messages(bin_transfer(pending, token_x, token_y, info.sender)))
 }
 
 // TODO: can we get rid of this?
@@ -1163,3 +1433,305 @@ pub fn approx_div(a: Uint256, b: Uint256) -> Uint256 {
         div
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lb_token::{_checkpoint_fees, accrued_fees, BALANCES, TOTAL_SUPPLIES};
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage},
+        ContractResult, OwnedDeps, SystemResult, WasmQuery,
+    };
+    use liquidity_book::testing::{mock_snip20_balance, mock_token, ACTIVE_ID};
+
+    fn setup_pair(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>) {
+        let storage = deps.as_mut().storage;
+
+        let mut parameters = PairParameters::default();
+        parameters
+            .set_static_fee_parameters(5000, 30, 600, 5000, 40000, 1000, 350000)
+            .unwrap()
+            .set_active_id(ACTIVE_ID)
+            .unwrap();
+
+        TOKEN_X.save(storage, &mock_token("token_x")).unwrap();
+        TOKEN_Y.save(storage, &mock_token("token_y")).unwrap();
+        VIEWING_KEY
+            .save(storage, &"viewing_key".to_string())
+            .unwrap();
+        HOOKS_PARAMETERS.save(storage, &None).unwrap();
+        BIN_STEP
+            .save(storage, &liquidity_book::testing::BIN_STEP)
+            .unwrap();
+        PARAMETERS.save(storage, &parameters).unwrap();
+        RESERVES.save(storage, &[0u8; 32]).unwrap();
+        PROTOCOL_FEES.save(storage, &[0u8; 32]).unwrap();
+    }
+
+    /// Mocks the SNIP-20 balances of the pair.
+    fn mock_balances(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        balance_x: u128,
+        balance_y: u128,
+    ) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "token_x" => {
+                SystemResult::Ok(ContractResult::Ok(mock_snip20_balance(balance_x)))
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "token_y" => {
+                SystemResult::Ok(ContractResult::Ok(mock_snip20_balance(balance_y)))
+            }
+            _ => panic!("unexpected query: {query:?}"),
+        });
+    }
+
+    /// Sends 1000 token X for a mint that also needs token Y, which leaves it pending.
+    fn receive_pending_mint(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        account: &str,
+    ) -> Result<Response> {
+        receive_mint(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(account, &[]),
+            Addr::unchecked("token_x"),
+            Uint128::new(1000),
+            account.to_string(),
+            vec![LiquidityConfigurations::encode_params(
+                500_000_000_000_000_000,
+                500_000_000_000_000_000,
+                ACTIVE_ID,
+            )],
+            account.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_burn_requires_approval() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("spender", &[]);
+
+        let err = burn(
+            &mut deps.as_mut(),
+            mock_env(),
+            info,
+            "owner".to_string(),
+            "spender".to_string(),
+            vec![8388608],
+            vec![Uint256::from(1u128)],
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::SpenderNotApproved { .. }));
+    }
+
+    #[test]
+    fn test_composition_fees_accrue_to_existing_shares() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let info = mock_info("minter", &[]);
+        let bin_step = 20u16;
+        let active_id = 8388608u32;
+
+        let mut parameters = PairParameters::default();
+        parameters
+            .set_static_fee_parameters(5000, 30, 600, 5000, 40000, 1000, 350000)
+            .unwrap()
+            .set_active_id(active_id)
+            .unwrap();
+        PARAMETERS.save(deps.as_mut().storage, &parameters).unwrap();
+        PROTOCOL_FEES
+            .save(deps.as_mut().storage, &[0u8; 32])
+            .unwrap();
+
+        let reserves = Bytes32::encode(1_000_000_000_000_000_000, 1_000_000_000_000_000_000);
+        let price = PriceHelper::get_price_from_id(active_id, bin_step).unwrap();
+        let supply = reserves.get_liquidity(price).unwrap().u256_to_uint256();
+        BINS.insert(deps.as_mut().storage, &active_id, &reserves)
+            .unwrap();
+        TOTAL_SUPPLIES
+            .insert(deps.as_mut().storage, &active_id, &supply)
+            .unwrap();
+        _checkpoint_fees(deps.as_mut().storage, &Addr::unchecked("alice"), active_id).unwrap();
+        BALANCES
+            .add_suffix(b"alice")
+            .insert(deps.as_mut().storage, &active_id, &supply)
+            .unwrap();
+
+        let mut events = Vec::new();
+        update_bin(
+            &mut deps.as_mut(),
+            &env,
+            &info,
+            &mut events,
+            bin_step,
+            active_id,
+            active_id,
+            Bytes32::encode_first(1_000_000_000_000_000_000),
+            parameters,
+        )
+        .unwrap();
+
+        let (fees_x, fees_y) = accrued_fees(deps.as_ref().storage, "alice", active_id).unwrap();
+
+        assert_eq!(events.len(), 1);
+        assert!(fees_x > 0);
+        assert_eq!(fees_y, 0);
+    }
+
+    #[test]
+    fn test_receive_mint_rejects_mismatched_leg() {
+        let mut deps = mock_dependencies();
+        setup_pair(&mut deps);
+
+        receive_pending_mint(&mut deps, "alice").unwrap();
+
+        let err = receive_mint(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice", &[]),
+            Addr::unchecked("token_y"),
+            Uint128::new(1000),
+            "mallory".to_string(),
+            vec![LiquidityConfigurations::encode_params(
+                500_000_000_000_000_000,
+                500_000_000_000_000_000,
+                ACTIVE_ID,
+            )],
+            "alice".to_string(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::PendingMintMismatch { .. }));
+        assert_eq!(
+            PENDING_MINTS
+                .get(deps.as_ref().storage, &Addr::unchecked("alice"))
+                .unwrap()
+                .amounts,
+            Bytes32::encode_first(1000)
+        );
+    }
+
+    #[test]
+    fn test_pending_mint_cannot_be_swapped() {
+        let mut deps = mock_dependencies();
+        setup_pair(&mut deps);
+        mock_balances(&mut deps, 1000, 0);
+
+        receive_pending_mint(&mut deps, "alice").unwrap();
+
+        let err = swap(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mallory", &[]),
+            true,
+            "mallory".to_string(),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::InsufficientAmountIn));
+    }
+
+    #[test]
+    fn test_pending_mint_cannot_be_flash_borrowed() {
+        let mut deps = mock_dependencies();
+        setup_pair(&mut deps);
+
+        receive_pending_mint(&mut deps, "alice").unwrap();
+
+        let err = flash_loan(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("mallory", &[]),
+            ContractInfo {
+                address: Addr::unchecked("receiver"),
+                code_hash: "receiver_hash".to_string(),
+            },
+            Bytes32::encode_first(1000),
+            None,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, Error::FlashLoanExceedsReserves));
+    }
+
+    #[test]
+    fn test_withdraw_pending_mint() {
+        let mut deps = mock_dependencies();
+        setup_pair(&mut deps);
+
+        receive_pending_mint(&mut deps, "alice").unwrap();
+
+        let res =
+            withdraw_pending_mint(deps.as_mut(), mock_env(), mock_info("alice", &[])).unwrap();
+
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|m| m.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![mock_token("token_x")
+                .transfer(Uint128::new(1000), Addr::unchecked("alice"))
+                .unwrap()]
+        );
+        assert_eq!(
+            pending_mints_total(deps.as_ref().storage).unwrap(),
+            [0u8; 32]
+        );
+    }
+
+    #[test]
+    fn test_swap_stops_at_id_limit_and_refunds_sender() {
+        let mut deps = mock_dependencies();
+        setup_pair(&mut deps);
+        mock_balances(&mut deps, 10_000, 2000);
+
+        for id in [ACTIVE_ID, ACTIVE_ID - 1] {
+            BINS.insert(deps.as_mut().storage, &id, &Bytes32::encode_second(1000))
+                .unwrap();
+            TREE.add(deps.as_mut().storage, id).unwrap();
+        }
+        RESERVES
+            .save(deps.as_mut().storage, &Bytes32::encode_second(2000))
+            .unwrap();
+
+        let res = swap(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("router", &[]),
+            true,
+            "bob".to_string(),
+            Some(ACTIVE_ID),
+        )
+        .unwrap();
+
+        let (reserve_x, reserve_y) = RESERVES.load(deps.as_ref().storage).unwrap().decode();
+        assert_eq!(reserve_y, 1000);
+        assert_eq!(
+            BINS.get(deps.as_ref().storage, &(ACTIVE_ID - 1)).unwrap(),
+            Bytes32::encode_second(1000)
+        );
+        assert_eq!(
+            PARAMETERS
+                .load(deps.as_ref().storage)
+                .unwrap()
+                .get_active_id(),
+            ACTIVE_ID
+        );
+        assert_eq!(
+            res.messages
+                .iter()
+                .map(|m| m.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                mock_token("token_y")
+                    .transfer(Uint128::new(1000), Addr::unchecked("bob"))
+                    .unwrap(),
+                mock_token("token_x")
+                    .transfer(Uint128::new(10_000 - reserve_x), Addr::unchecked("router"))
+                    .unwrap(),
+            ]
+        );
+    }
+}
@@ -1,16 +1,24 @@
-use crate::{state::*, Error, Result};
-use cosmwasm_std::{Addr, ContractInfo, CosmosMsg, Deps, Env, QuerierWrapper, StdResult};
+use crate::{lb_token::TOTAL_SUPPLIES, state::*, Error, Result};
+use cosmwasm_std::{
+    Addr, ContractInfo, CosmosMsg, Deps, Env, MessageInfo, QuerierWrapper, StdResult, Storage,
+};
 use ethnum::U256;
 use liquidity_book::{
     core::TokenType,
     interfaces::lb_token,
     libraries::{
-        math::{packed_u128_math::PackedUint128Math, uint256_to_u256::ConvertUint256},
+        constants::SCALE_OFFSET,
+        math::{
+            packed_u128_math::PackedUint128Math,
+            u256x256_math::U256x256Math,
+            uint256_to_u256::{ConvertU256, ConvertUint256},
+        },
         Bytes32,
     },
 };
-use secret_toolkit::snip20::{register_receive_msg, set_viewing_key_msg};
-use shade_protocol::snip20;
+use secret_toolkit::snip20::{
+    balance_query, register_receive_msg, set_viewing_key_msg, token_info_query,
+};
 
 // TODO: make a 'bin' type with these methods?
 
@@ -20,17 +28,16 @@ pub fn _balance_of(
     token: &ContractInfo,
     viewing_key: String,
 ) -> u128 {
-    querier
-        .query_wasm_smart::<secret_toolkit::snip20::Balance>(
-            token.code_hash.clone(),
-            token.address.clone(),
-            &snip20::QueryMsg::Balance {
-                address: env.contract.address.to_string(),
-                key: viewing_key,
-            },
-        )
-        .map(|response| response.amount.u128())
-        .expect("issue querying the contract's snip20 balance")
+    balance_query(
+        querier,
+        env.contract.address.to_string(),
+        viewing_key,
+        256,
+        token.code_hash.clone(),
+        token.address.to_string(),
+    )
+    .map(|response| response.amount.u128())
+    .expect("issue querying the contract's snip20 balance")
 }
 
 /// Transfers the encoded amounts to the recipient for both tokens.
@@ -200,20 +207,9 @@ pub fn _get_total_supply(deps: Deps, id: u32) -> Result<U256> {
 }
 
 pub fn query_token_symbol(deps: Deps, code_hash: String, address: Addr) -> Result<String> {
-    let msg = snip20::QueryMsg::TokenInfo {};
-
-    let res = deps.querier.query_wasm_smart::<snip20::QueryAnswer>(
-        code_hash,
-        address.to_string(),
-        &(&msg),
-    )?;
-
-    let symbol = match res {
-        snip20::QueryAnswer::TokenInfo { symbol, .. } => symbol,
-        _ => return Err(Error::Generic(format!("Token {} not valid", address))),
-    };
+    let token_info = token_info_query(deps.querier, 256, code_hash, address.to_string())?;
 
-    Ok(symbol)
+    Ok(token_info.symbol)
 }
 
 /// Returns id of the next non-empty bin.
@@ -229,6 +225,95 @@ pub fn _get_next_non_empty_bin(deps: Deps, swap_for_y: bool, id: u32) -> u32 {
     }
 }
 
+/// Adds the LP `fees` collected by the bin `id` to its cumulative fees per share.
+///
+/// # Arguments
+///
+/// * `id` - The id of the bin
+/// * `fees` - The fees left in the bin, excluding the protocol fees, encoded as follows:
+///     * [0 - 128[: fees_x
+///     * [128 - 256[: fees_y
+pub fn accrue_fees_per_share(storage: &mut dyn Storage, id: u32, fees: Bytes32) -> Result<()> {
+    let total_supply = TOTAL_SUPPLIES
+        .get(storage, &id)
+        .unwrap_or_default()
+        .uint256_to_u256();
+
+    if total_supply == U256::ZERO || fees == [0u8; 32] {
+        return Ok(());
+    }
+
+    let (fees_x, fees_y) = fees.decode();
+    let mut fees_per_share = FEES_PER_SHARE.get(storage, &id).unwrap_or_default();
+
+    fees_per_share.x = fees_per_share
+        .x
+        .uint256_to_u256()
+        .wrapping_add(U256::from(fees_x).shift_div_round_down(SCALE_OFFSET, total_supply)?)
+        .u256_to_uint256();
+    fees_per_share.y = fees_per_share
+        .y
+        .uint256_to_u256()
+        .wrapping_add(U256::from(fees_y).shift_div_round_down(SCALE_OFFSET, total_supply)?)
+        .u256_to_uint256();
+
+    FEES_PER_SHARE.insert(storage, &id, &fees_per_share)?;
+
+    Ok(())
+}
+
+/// Returns true if a swap in the given direction must not use the bin `id`, because it is past
+/// the `id_limit`.
+pub fn is_past_id_limit(swap_for_y: bool, id: u32, id_limit: Option<u32>) -> bool {
+    match id_limit {
+        Some(id_limit) if swap_for_y => id < id_limit,
+        Some(id_limit) => id > id_limit,
+        None => false,
+    }
+}
+
+/// Checks that the funds sent along with the message are only native tokens of this pair.
+///
+/// Native tokens don't need to be transferred separately: the funds are added to the bank balance
+/// of the pair before the message is executed, so they are accounted for like any other deposit.
+pub fn check_native_funds(
+    info: &MessageInfo,
+    token_x: &TokenType,
+    token_y: &TokenType,
+) -> Result<()> {
+    for coin in &info.funds {
+        let is_pair_denom = |token: &TokenType| match token {
+            TokenType::NativeToken { denom } => denom == &coin.denom,
+            TokenType::CustomToken { .. } => false,
+        };
+
+        if !is_pair_denom(token_x) && !is_pair_denom(token_y) {
+            return Err(Error::UnsupportedNativeToken {
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the tokens held for pending mints, which must be excluded from the received amounts.
+pub fn pending_mints_total(storage: &dyn Storage) -> StdResult<Bytes32> {
+    Ok(PENDING_MINTS_TOTAL.may_load(storage)?.unwrap_or_default())
+}
+
+/// Modifier to prevent reentrancy while a flash loan is in progress.
+///
+/// The flash loan state is only stored between the callback submessage and its reply, so
+/// its presence means the receiver contract is calling back into this pair.
+pub fn non_reentrant(storage: &dyn Storage) -> Result<()> {
+    if EPHEMERAL_FLASH_LOAN.may_load(storage)?.is_some() {
+        return Err(Error::ReentrantCall);
+    }
+
+    Ok(())
+}
+
 pub fn only_factory(sender: &Addr, factory: &Addr) -> Result<()> {
     if sender != factory {
         return Err(Error::OnlyFactory);
@@ -237,6 +322,17 @@ pub fn only_factory(sender: &Addr, factory: &Addr) -> Result<()> {
     Ok(())
 }
 
+/// Modifier to check that `sender` is the factory, or the owner of the factory.
+pub fn only_factory_or_owner(deps: Deps, sender: &Addr) -> Result<()> {
+    let factory = FACTORY.load(deps.storage)?;
+
+    if *sender != factory.address && *sender != factory.get_owner(deps.querier)? {
+        return Err(Error::OnlyFactoryOrOwner);
+    }
+
+    Ok(())
+}
+
 pub fn only_protocol_fee_recipient(sender: &Addr, recipient: &Addr) -> Result<()> {
     if sender != recipient {
         return Err(Error::OnlyProtocolFeeRecipient);
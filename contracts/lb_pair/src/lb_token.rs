@@ -6,25 +6,35 @@ pub use liquidity_book::interfaces::lb_token2::LbTokenError as Error;
 /// Alias for Result<T, LbTokenError>
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
+use crate::state::{Allowance, FeeCheckpoint, FEES_PER_SHARE};
 use cosmwasm_std::{
-    Addr, Deps, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult, Uint256,
+    to_binary, Addr, BlockInfo, Deps, DepsMut, Env, Event, MessageInfo, Response, StdError,
+    StdResult, Storage, Uint128, Uint256,
+};
+use liquidity_book::{
+    interfaces::{
+        lb_pair::{AllowanceResponse, CreateViewingKeyResponse},
+        lb_token::expiration::Expiration,
+        lb_token2::*,
+    },
+    libraries::{
+        constants::SCALE_OFFSET,
+        math::{u256x256_math::U256x256Math, uint256_to_u256::ConvertUint256},
+    },
 };
-use liquidity_book::interfaces::lb_token2::*;
 use secret_toolkit::{
+    permit::{validate, Permit, RevokedPermits, TokenPermissions},
     serialization::Bincode2,
-    storage::{Item, Keymap, KeymapBuilder, WithoutIter},
+    storage::{Item, Keymap, KeymapBuilder, Keyset, WithoutIter},
+    viewing_key::{ViewingKey, ViewingKeyStore},
 };
 
 // TODO: There is no TOTAL total supply function... which kinda makes sense, since there are so
 // many bins. It would cost more gas to always track the total supply, but maybe it's worth it?
 // This total supply would represent the "total liquidity" or the largest amount that can be swapped.
 
-// TODO: while we're at it, we could add even more state to track which bins each user has
-// liquidity in, and each user's total liquidity (does that even mean anything)? Technically,
-// we have the ability to iterate over the Keymap, though we should probably disable that for gas savings.
-// I think we should keep it for Balances, disable it for total supplies. because each user's
-// balances map is likely much smaller than the total supplies map. And if we keep a separate tally
-// of total supply, we never need to iterate over total supplies.
+// NOTE: each user's non-zero bins are tracked in USER_BINS, so we never need to iterate over
+// the BALANCES keymap (or scan every id with balance_of_batch) to find a user's positions.
 
 // TODO: U256 is serialized as a "0x" prefixed hex strings by default. Figure out how to make it
 // use bytes instead.
@@ -65,9 +75,21 @@ pub(crate) static BALANCES: Keymap<u32, Uint256> = Keymap::new(b"balances");
 pub(crate) static TOTAL_SUPPLIES: Keymap<u32, Uint256, Bincode2, WithoutIter> =
     KeymapBuilder::new(b"total_supplies").without_iter().build();
 
+/// The set of bin ids in which each account has a non-zero balance.
+pub(crate) static USER_BINS: Keyset<u32> = Keyset::new(b"user_bins");
+
+/// The mapping from account to token id to the fee checkpoint of the account in that bin.
+pub(crate) static FEE_CHECKPOINTS: Keymap<u32, FeeCheckpoint> = Keymap::new(b"fee_checkpoints");
+
 /// Mapping from account to spender approvals.
 pub(crate) static SPENDER_APPROVALS: Keymap<String, bool> = Keymap::new(b"spender_approvals");
 
+/// Mapping from account to spender to token id to allowance.
+pub(crate) static ALLOWANCES: Keymap<u32, Allowance> = Keymap::new(b"allowances");
+
+/// Storage prefix for the revoked query permits.
+pub(crate) const PREFIX_REVOKED_PERMITS: &str = "revokedperms";
+
 /// Modifier to check if the spender is approved for all.
 pub fn check_approval(deps: Deps, from: String, spender: String) -> Result<()> {
     if !_is_approved_for_all(deps, &from, &spender) {
@@ -121,8 +143,6 @@ pub fn total_supply(deps: Deps, id: u32) -> Uint256 {
     TOTAL_SUPPLIES.get(deps.storage, &id).unwrap_or_default()
 }
 
-// TODO: viewing keys
-
 /// Returns the amount of tokens of type `id` owned by `account`.
 pub fn balance_of(deps: Deps, account: String, id: u32) -> Uint256 {
     // Ok(BalanceResponse {
@@ -164,6 +184,78 @@ pub fn is_approved_for_all(deps: Deps, owner: String, spender: String) -> bool {
     _is_approved_for_all(deps, &owner, &spender)
 }
 
+/// Creates a viewing key for the caller, used to authenticate balance queries.
+pub fn create_viewing_key(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entropy: String,
+) -> Result<Response> {
+    let key = ViewingKey::create(
+        deps.storage,
+        &info,
+        &env,
+        info.sender.as_str(),
+        entropy.as_ref(),
+    );
+
+    Ok(Response::new().set_data(to_binary(&CreateViewingKeyResponse { key })?))
+}
+
+/// Sets the viewing key of the caller.
+pub fn set_viewing_key(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    key: String,
+) -> Result<Response> {
+    ViewingKey::set(deps.storage, info.sender.as_str(), key.as_str());
+
+    Ok(Response::new())
+}
+
+/// Revokes a query permit previously signed by the caller.
+pub fn revoke_permit(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    permit_name: String,
+) -> Result<Response> {
+    RevokedPermits::revoke_permit(
+        deps.storage,
+        PREFIX_REVOKED_PERMITS,
+        info.sender.as_str(),
+        &permit_name,
+    );
+
+    Ok(Response::new())
+}
+
+/// Checks that `key` is the viewing key of `account`.
+pub fn check_viewing_key(deps: Deps, account: &str, key: &str) -> Result<()> {
+    ViewingKey::check(deps.storage, account, key).map_err(|_| Error::Unauthorized)
+}
+
+/// Validates a query permit signed for this contract, and returns the account that signed it.
+pub fn validate_permit(deps: Deps, env: &Env, permit: &Permit) -> Result<String> {
+    let account = validate(
+        deps,
+        PREFIX_REVOKED_PERMITS,
+        permit,
+        env.contract.address.to_string(),
+        None,
+    )?;
+
+    if !permit.check_permission(&TokenPermissions::Owner) {
+        return Err(Error::OwnerPermitRequired(format!(
+            "{:?}",
+            permit.params.permissions
+        )));
+    }
+
+    Ok(account)
+}
+
 /// Grants or revokes permission to `spender` to transfer the caller's tokens, according to `approved`.
 pub fn approve_for_all(
     deps: DepsMut,
@@ -175,7 +267,59 @@ pub fn approve_for_all(
     _approve_for_all(deps, info.sender.to_string(), spender, approved)
 }
 
+/// Sets the amount of shares of the bin `id` that `spender` can transfer on behalf of the caller.
+pub fn approve(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    id: u32,
+    amount: Uint256,
+    expiration: Option<Expiration>,
+) -> Result<Response> {
+    let spender = deps.api.addr_validate(&spender)?;
+
+    _approve(
+        deps.storage,
+        &info.sender,
+        &spender,
+        id,
+        amount,
+        expiration.unwrap_or_default(),
+    )
+}
+
+/// Returns the shares of the bin `id` that `spender` can transfer on behalf of `owner`.
+pub fn allowance(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    spender: String,
+    id: u32,
+) -> AllowanceResponse {
+    let allowance = ALLOWANCES
+        .add_suffix(owner.as_bytes())
+        .add_suffix(spender.as_bytes())
+        .get(deps.storage, &id)
+        .unwrap_or(Allowance {
+            amount: Uint256::zero(),
+            expiration: Expiration::Never,
+        });
+
+    AllowanceResponse {
+        amount: if allowance.expiration.is_expired(&env.block) {
+            Uint256::zero()
+        } else {
+            allowance.amount
+        },
+        expiration: allowance.expiration,
+    }
+}
+
 /// Batch transfers `amounts` of `ids` from `from` to `to`.
+///
+/// A spender that is not approved for all must have an allowance for each id, which is consumed
+/// by the transfer.
 pub fn batch_transfer_from(
     deps: DepsMut,
     env: Env,
@@ -185,11 +329,21 @@ pub fn batch_transfer_from(
     ids: Vec<u32>,
     amounts: Vec<Uint256>,
 ) -> Result<Response> {
-    check_approval(deps.as_ref(), from.clone(), info.sender.to_string())?;
-
     let from = deps.api.addr_validate(&from)?;
     let to = deps.api.addr_validate(&to)?;
 
+    if !_is_approved_for_all(deps.as_ref(), &from.to_string(), &info.sender.to_string()) {
+        check_length(ids.len(), amounts.len())?;
+        _spend_allowances(
+            deps.storage,
+            &env.block,
+            &from,
+            &info.sender,
+            &ids,
+            &amounts,
+        )?;
+    }
+
     _batch_transfer_from(deps, env, info, from, to, ids, amounts)
 }
 
@@ -220,6 +374,8 @@ pub(crate) fn _mint(deps: &mut DepsMut, account: Addr, id: u32, amount: Uint256)
         Ok(total_supply)
     })?;
 
+    _checkpoint_fees(deps.storage, &account, id)?;
+
     let mut balance = BALANCES
         .add_suffix(account.as_bytes())
         .get(deps.storage, &id)
@@ -231,6 +387,12 @@ pub(crate) fn _mint(deps: &mut DepsMut, account: Addr, id: u32, amount: Uint256)
         .add_suffix(account.as_bytes())
         .insert(deps.storage, &id, &balance)?;
 
+    if !balance.is_zero() {
+        USER_BINS
+            .add_suffix(account.as_bytes())
+            .insert(deps.storage, &id)?;
+    }
+
     Ok(())
 
     // Original:
@@ -259,6 +421,8 @@ pub(crate) fn _burn(deps: &mut DepsMut, account: Addr, id: u32, amount: Uint256)
         });
     }
 
+    _checkpoint_fees(deps.storage, &account, id)?;
+
     let mut bin_total_supply = TOTAL_SUPPLIES
         .get(deps.storage, &id)
         .expect("attempting to burn when total supply is zero");
@@ -269,6 +433,12 @@ pub(crate) fn _burn(deps: &mut DepsMut, account: Addr, id: u32, amount: Uint256)
 
     account_balances.insert(deps.storage, &id, &(balance - amount))?;
 
+    if balance == amount && !amount.is_zero() {
+        USER_BINS
+            .add_suffix(account.as_bytes())
+            .remove(deps.storage, &id)?;
+    }
+
     // TODO: potentially
     TOTAL_SUPPLY.update(deps.storage, |mut total_supply| -> StdResult<_> {
         total_supply -= amount;
@@ -302,6 +472,8 @@ pub(crate) fn _batch_transfer_from(
 
     let from_balances = BALANCES.add_suffix(from.as_bytes());
     let to_balances = BALANCES.add_suffix(to.as_bytes());
+    let from_bins = USER_BINS.add_suffix(from.as_bytes());
+    let to_bins = USER_BINS.add_suffix(to.as_bytes());
 
     for i in 0..ids.len() {
         let id = ids[i];
@@ -312,10 +484,20 @@ pub(crate) fn _batch_transfer_from(
             return Err(Error::TransferExceedsBalance { from, id, amount });
         }
 
+        _checkpoint_fees(deps.storage, &from, id)?;
+        _checkpoint_fees(deps.storage, &to, id)?;
+
         from_balances.insert(deps.storage, &id, &(from_balance - amount))?;
 
         let to_balance = to_balances.get(deps.storage, &id).unwrap_or_default();
         to_balances.insert(deps.storage, &id, &(to_balance + amount))?;
+
+        if !amount.is_zero() {
+            if from_balance == amount {
+                from_bins.remove(deps.storage, &id)?;
+            }
+            to_bins.insert(deps.storage, &id)?;
+        }
     }
 
     let event = Event::transfer_batch(info.sender, from.to_string(), to.to_string(), ids, amounts);
@@ -364,6 +546,79 @@ pub(crate) fn _approve_for_all(
     Ok(Response::new().add_event(event))
 }
 
+pub(crate) fn _approve(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    id: u32,
+    amount: Uint256,
+    expiration: Expiration,
+) -> Result<Response> {
+    if owner == spender {
+        return Err(Error::SelfApproval(owner.to_string()));
+    }
+
+    ALLOWANCES
+        .add_suffix(owner.as_bytes())
+        .add_suffix(spender.as_bytes())
+        .insert(storage, &id, &Allowance { amount, expiration })?;
+
+    let event = Event::approval(
+        owner.to_string(),
+        spender.to_string(),
+        id,
+        amount,
+        expiration,
+    );
+
+    Ok(Response::new().add_event(event))
+}
+
+/// Deducts `amounts` of `ids` from the allowances given by `owner` to `spender`.
+/// Expired allowances are treated as zero.
+pub(crate) fn _spend_allowances(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    owner: &Addr,
+    spender: &Addr,
+    ids: &[u32],
+    amounts: &[Uint256],
+) -> Result<()> {
+    let allowances = ALLOWANCES
+        .add_suffix(owner.as_bytes())
+        .add_suffix(spender.as_bytes());
+
+    for (id, amount) in ids.iter().zip(amounts) {
+        let mut allowance = allowances
+            .get(storage, id)
+            .filter(|allowance| !allowance.expiration.is_expired(block))
+            .ok_or_else(|| Error::SpenderNotApproved {
+                from: owner.to_string(),
+                spender: spender.to_string(),
+            })?;
+
+        if allowance.amount < *amount {
+            return Err(Error::InsufficientAllowance {
+                from: owner.to_string(),
+                spender: spender.to_string(),
+                id: *id,
+                allowance: allowance.amount,
+                amount: *amount,
+            });
+        }
+
+        allowance.amount -= *amount;
+
+        if allowance.amount.is_zero() {
+            allowances.remove(storage, id)?;
+        } else {
+            allowances.insert(storage, id, &allowance)?;
+        }
+    }
+
+    Ok(())
+}
+
 pub(crate) fn _not_address_zero_or_this(env: Env, account: String) -> Result<()> {
     if account == "" || account == env.contract.address.to_string() {
         Err(Error::AddressThisOrZero)
@@ -380,15 +635,80 @@ pub(crate) fn _check_length(length_a: usize, length_b: usize) -> Result<()> {
     }
 }
 
-// TODO: test if this works
-pub(crate) fn _get_all_user_bins(deps: Deps, account: String) -> StdResult<Vec<u32>> {
-    let user_balances = BALANCES.add_suffix(account.as_bytes());
+/// Returns the fees earned by `account` in the bin `id` since its first deposit, as
+/// `(fees_x, fees_y)`.
+///
+/// Balances without a checkpoint were created before the fees were tracked, so they have not
+/// accrued anything yet. Their first checkpoint starts from the current fees per share.
+pub fn accrued_fees(storage: &dyn Storage, account: &str, id: u32) -> StdResult<(u128, u128)> {
+    let Some(checkpoint) = FEE_CHECKPOINTS
+        .add_suffix(account.as_bytes())
+        .get(storage, &id)
+    else {
+        return Ok((0, 0));
+    };
+    let balance = BALANCES
+        .add_suffix(account.as_bytes())
+        .get(storage, &id)
+        .unwrap_or_default()
+        .uint256_to_u256();
+    let fees_per_share = FEES_PER_SHARE.get(storage, &id).unwrap_or_default();
+
+    let pending = |current: Uint256, last: Uint256| -> StdResult<u128> {
+        let growth = current
+            .uint256_to_u256()
+            .wrapping_sub(last.uint256_to_u256());
+
+        let fees = balance
+            .mul_shift_round_down(growth, SCALE_OFFSET)
+            .map_err(|e| StdError::generic_err(e.to_string()))?;
+
+        u128::try_from(fees).map_err(|_| StdError::generic_err("Accrued fees overflow"))
+    };
 
-    // won't be sorted
-    let keys: StdResult<Vec<u32>> = user_balances.iter_keys(deps.storage)?.collect();
+    let pending_x = pending(fees_per_share.x, checkpoint.fees_per_share.x)?;
+    let pending_y = pending(fees_per_share.y, checkpoint.fees_per_share.y)?;
+
+    let fees_x = checkpoint.accrued_x.checked_add(Uint128::new(pending_x))?;
+    let fees_y = checkpoint.accrued_y.checked_add(Uint128::new(pending_y))?;
+
+    Ok((fees_x.u128(), fees_y.u128()))
+}
+
+/// Settles the fees accrued by `account` in the bin `id` with its current balance.
+/// Must be called before every balance change.
+pub(crate) fn _checkpoint_fees(
+    storage: &mut dyn Storage,
+    account: &Addr,
+    id: u32,
+) -> StdResult<()> {
+    let (accrued_x, accrued_y) = accrued_fees(storage, account.as_str(), id)?;
+
+    FEE_CHECKPOINTS.add_suffix(account.as_bytes()).insert(
+        storage,
+        &id,
+        &FeeCheckpoint {
+            fees_per_share: FEES_PER_SHARE.get(storage, &id).unwrap_or_default(),
+            accrued_x: Uint128::new(accrued_x),
+            accrued_y: Uint128::new(accrued_y),
+        },
+    )
+}
 
-    keys.and_then(|mut vec| {
-        vec.sort_unstable();
-        Ok(vec)
-    })
+/// Returns a page of the bin ids in which `account` has a non-zero balance, and the total
+/// number of such bins.
+///
+/// The ids are returned in the order they are stored in, which is not sorted.
+pub fn user_bins(
+    deps: Deps,
+    account: &str,
+    page: u32,
+    page_size: u32,
+) -> StdResult<(Vec<u32>, u32)> {
+    let user_bins = USER_BINS.add_suffix(account.as_bytes());
+
+    let ids = user_bins.paging(deps.storage, page, page_size)?;
+    let total = user_bins.get_len(deps.storage)?;
+
+    Ok((ids, total))
 }
@@ -10,7 +10,7 @@ mod state;
 
 mod lb_token;
 
-pub use contract::{execute, instantiate, query, reply};
+pub use contract::{execute, instantiate, migrate, query, reply};
 
 // Use this crate's custom Error type
 pub use liquidity_book::interfaces::lb_pair::LbPairError as Error;
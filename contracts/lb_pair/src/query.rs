@@ -1,4 +1,9 @@
-use crate::{helper::*, state::*, Result};
+use crate::{
+    helper::*,
+    lb_token::{accrued_fees, balance_of, total_supply, user_bins},
+    state::*,
+    Error, Result,
+};
 use cosmwasm_std::{Deps, Env, Uint128, Uint256};
 use ethnum::U256;
 use liquidity_book::{
@@ -9,11 +14,18 @@ use liquidity_book::{
             u24::U24,
             uint256_to_u256::{ConvertU256, ConvertUint256},
         },
-        oracle_helper, BinHelper, Bytes32, FeeHelper, OracleMap, PackedUint128Math, PriceHelper,
-        U256x256Math,
+        oracle_helper, BinHelper, Bytes32, FeeHelper, OracleMap, PackedUint128Math, PairParameters,
+        PriceHelper, U256x256Math,
     },
 };
 
+const DEFAULT_BINS_LIMIT: u32 = 100;
+const MAX_BINS_LIMIT: u32 = 500;
+const MAX_DEPTH_RADIUS: u32 = 250;
+const MAX_LOOKUP_TIMESTAMPS: usize = 100;
+const DEFAULT_USER_BINS_PAGE_SIZE: u32 = 10;
+const MAX_USER_BINS_PAGE_SIZE: u32 = 100;
+
 // for the commented out function below
 // use shade_protocol::{
 //     swap::{
@@ -216,6 +228,149 @@ pub fn get_all_bins(
     })
 }
 
+/// Returns the non-empty bins between `start_id` and `end_id` (both inclusive), in the given direction.
+///
+/// When walking in `Ascending` order, `start_id` defaults to the lowest id and `end_id` to the highest,
+/// and the other way around for `Descending`. At most `limit` bins are returned.
+///
+/// # Returns
+///
+/// * `bins` - The reserves, total supply of shares and price of each bin
+/// * `next_id` - The id of the next non-empty bin in the range, to be used as the `start_id` of the
+///   next query, or `None` once the range is exhausted
+pub fn get_bins_in_range(
+    deps: Deps,
+    start_id: Option<u32>,
+    end_id: Option<u32>,
+    direction: Direction,
+    limit: Option<u32>,
+) -> Result<BinsInRangeResponse> {
+    let limit = limit.unwrap_or(DEFAULT_BINS_LIMIT).min(MAX_BINS_LIMIT) as usize;
+    let bin_step = BIN_STEP.load(deps.storage)?;
+
+    let (start_id, end_id) = match direction {
+        Direction::Ascending => (start_id.unwrap_or(0), end_id.unwrap_or(U24::MAX)),
+        Direction::Descending => (start_id.unwrap_or(U24::MAX), end_id.unwrap_or(0)),
+    };
+
+    if start_id > U24::MAX || end_id > U24::MAX {
+        return Err(Error::U24Overflow);
+    }
+
+    let in_range = |id: &u32| match direction {
+        Direction::Ascending => *id <= end_id,
+        Direction::Descending => *id >= end_id,
+    };
+
+    // The tree returns 0 (left) or U24::MAX (right) when there is no such bin.
+    let next_non_empty_bin = |id: u32| match direction {
+        Direction::Ascending => Some(TREE.find_first_left(deps.storage, id)).filter(|id| *id != 0),
+        Direction::Descending => {
+            Some(TREE.find_first_right(deps.storage, id)).filter(|id| *id != U24::MAX)
+        }
+    };
+
+    let mut cursor = if TREE.contains(deps.storage, start_id) {
+        Some(start_id)
+    } else {
+        next_non_empty_bin(start_id)
+    };
+
+    let mut bins = Vec::new();
+
+    while let Some(id) = cursor.filter(in_range) {
+        if bins.len() == limit {
+            break;
+        }
+
+        let (bin_reserve_x, bin_reserve_y) =
+            BINS.get(deps.storage, &id).unwrap_or_default().decode();
+
+        bins.push(BinInRangeResponse {
+            bin_id: id,
+            bin_reserve_x: bin_reserve_x.into(),
+            bin_reserve_y: bin_reserve_y.into(),
+            total_supply: total_supply(deps, id),
+            price: PriceHelper::get_price_from_id(id, bin_step)?.u256_to_uint256(),
+        });
+
+        cursor = next_non_empty_bin(id);
+    }
+
+    Ok(BinsInRangeResponse {
+        bins,
+        next_id: cursor.filter(in_range),
+    })
+}
+
+/// Returns the liquidity of the non-empty bins within `radius` bins of the active id, sorted by id.
+///
+/// Each bin also includes the cumulative amounts of tokens X and Y from the active bin down to
+/// that bin, or from the first bin above the active bin up to that bin, which is what a depth chart
+/// plots. The active bin is only counted on the lower side. The radius is capped at `MAX_DEPTH_RADIUS`.
+pub fn get_liquidity_depth(deps: Deps, radius: u32) -> Result<LiquidityDepthResponse> {
+    let active_id = PARAMETERS.load(deps.storage)?.get_active_id();
+    let bin_step = BIN_STEP.load(deps.storage)?;
+
+    let radius = radius.min(MAX_DEPTH_RADIUS);
+    let lowest_id = active_id.saturating_sub(radius);
+    let highest_id = active_id.saturating_add(radius).min(U24::MAX);
+
+    let depth_bin = |id: u32, cumulative_x: u128, cumulative_y: u128| -> Result<_> {
+        let (bin_reserve_x, bin_reserve_y) =
+            BINS.get(deps.storage, &id).unwrap_or_default().decode();
+
+        Ok(LiquidityDepthBinResponse {
+            bin_id: id,
+            price: PriceHelper::get_price_from_id(id, bin_step)?.u256_to_uint256(),
+            bin_reserve_x: bin_reserve_x.into(),
+            bin_reserve_y: bin_reserve_y.into(),
+            cumulative_x: (cumulative_x + bin_reserve_x).into(),
+            cumulative_y: (cumulative_y + bin_reserve_y).into(),
+        })
+    };
+
+    let mut bins_below = Vec::new();
+    let (mut cumulative_x, mut cumulative_y) = (0u128, 0u128);
+
+    // Walk down from the active bin, including it.
+    let mut id = if TREE.contains(deps.storage, active_id) {
+        active_id
+    } else {
+        TREE.find_first_right(deps.storage, active_id)
+    };
+
+    while id != U24::MAX && id >= lowest_id {
+        let bin = depth_bin(id, cumulative_x, cumulative_y)?;
+        (cumulative_x, cumulative_y) = (bin.cumulative_x.u128(), bin.cumulative_y.u128());
+        bins_below.push(bin);
+
+        id = TREE.find_first_right(deps.storage, id);
+    }
+
+    // Walk up from the active bin, excluding it, as it was already counted on the lower side.
+    let (mut cumulative_x, mut cumulative_y) = (0u128, 0u128);
+    let mut bins_above = Vec::new();
+
+    id = TREE.find_first_left(deps.storage, active_id);
+
+    while id != 0 && id <= highest_id {
+        let bin = depth_bin(id, cumulative_x, cumulative_y)?;
+        (cumulative_x, cumulative_y) = (bin.cumulative_x.u128(), bin.cumulative_y.u128());
+        bins_above.push(bin);
+
+        id = TREE.find_first_left(deps.storage, id);
+    }
+
+    bins_below.reverse();
+    bins_below.extend(bins_above);
+
+    Ok(LiquidityDepthResponse {
+        active_id,
+        bins: bins_below,
+    })
+}
+
 /// Returns the next non-empty bin.
 ///
 /// The next non-empty bin is the bin with a higher (if swap_for_y is true) or lower (if swap_for_y is false)
@@ -330,23 +485,124 @@ pub fn get_oracle_params(deps: Deps) -> Result<OracleParametersResponse> {
     }
 }
 
+/// Returns which operations of the Liquidity Book Pair are currently paused.
+pub fn get_paused_operations(deps: Deps) -> Result<PausedOperationsResponse> {
+    let paused_operations = PAUSED_OPERATIONS.load(deps.storage)?;
+    let is_paused = |operation: u8| paused_operations & operation != 0;
+
+    Ok(PausedOperationsResponse {
+        paused_operations,
+        swap: is_paused(PAUSE_SWAP),
+        mint: is_paused(PAUSE_MINT),
+        burn: is_paused(PAUSE_BURN),
+        flash_loan: is_paused(PAUSE_FLASH_LOAN),
+        transfer: is_paused(PAUSE_TRANSFER),
+        collect_protocol_fees: is_paused(PAUSE_COLLECT_PROTOCOL_FEES),
+    })
+}
+
 /// Returns the cumulative values of the Liquidity Book Pair at a given timestamp.
 pub fn get_oracle_sample_at(
     deps: Deps,
     env: Env,
     lookup_timestamp: u64,
 ) -> Result<OracleSampleAtResponse> {
+    let sample = _get_oracle_sample_at(deps, &env, lookup_timestamp)?;
+
+    Ok(OracleSampleAtResponse { sample })
+}
+
+/// Returns the cumulative values of the Liquidity Book Pair at each of the given timestamps.
+pub fn get_oracle_samples_at(
+    deps: Deps,
+    env: Env,
+    lookup_timestamps: Vec<u64>,
+) -> Result<OracleSamplesAtResponse> {
+    if lookup_timestamps.len() > MAX_LOOKUP_TIMESTAMPS {
+        return Err(Error::TooManyLookupTimestamps {
+            count: lookup_timestamps.len(),
+            max: MAX_LOOKUP_TIMESTAMPS,
+        });
+    }
+
+    let samples = lookup_timestamps
+        .into_iter()
+        .map(|lookup_timestamp| _get_oracle_sample_at(deps, &env, lookup_timestamp))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(OracleSamplesAtResponse { samples })
+}
+
+/// Returns the time-weighted average price and volatility of the Liquidity Book Pair between
+/// `seconds_ago_start` and `seconds_ago_end`.
+///
+/// The average id is the difference of the cumulative ids at both ends of the window, divided by
+/// its duration. The price is the price of that average id.
+///
+/// # Arguments
+///
+/// * `seconds_ago_start` - How many seconds ago the window starts
+/// * `seconds_ago_end` - How many seconds ago the window ends, must be lower than `seconds_ago_start`
+pub fn get_twap(
+    deps: Deps,
+    env: Env,
+    seconds_ago_start: u64,
+    seconds_ago_end: u64,
+) -> Result<TwapResponse> {
+    let now = env.block.time.seconds();
+    let invalid_window = || Error::InvalidTwapWindow {
+        seconds_ago_start,
+        seconds_ago_end,
+    };
+
+    if seconds_ago_start <= seconds_ago_end {
+        return Err(invalid_window());
+    }
+
+    let start_timestamp = now
+        .checked_sub(seconds_ago_start)
+        .ok_or_else(invalid_window)?;
+    let end_timestamp = now - seconds_ago_end;
+    let duration = end_timestamp - start_timestamp;
+
+    if PARAMETERS.load(deps.storage)?.get_oracle_id() == 0 {
+        return Err(Error::OracleNotInitialized);
+    }
+
+    let start = _get_oracle_sample_at(deps, &env, start_timestamp)?;
+    let end = _get_oracle_sample_at(deps, &env, end_timestamp)?;
+
+    let average_id = ((end.cumulative_id - start.cumulative_id) / duration) as u32;
+    let average_volatility = (end.cumulative_volatility - start.cumulative_volatility) / duration;
+    let bins_crossed = end.cumulative_bin_crossed - start.cumulative_bin_crossed;
+
+    let bin_step = BIN_STEP.load(deps.storage)?;
+    let price = PriceHelper::get_price_from_id(average_id, bin_step)?;
+    let price_decimal = PriceHelper::convert128x128_price_to_decimal(price)?;
+
+    Ok(TwapResponse {
+        average_id,
+        price: price.u256_to_uint256(),
+        price_decimal: price_decimal.u256_to_uint256(),
+        average_volatility,
+        bins_crossed,
+    })
+}
+
+fn _get_oracle_sample_at(
+    deps: Deps,
+    env: &Env,
+    lookup_timestamp: u64,
+) -> Result<OracleSampleResponse> {
     let mut parameters = PARAMETERS.load(deps.storage)?;
 
     let oracle_id = parameters.get_oracle_id();
 
     if oracle_id == 0 || lookup_timestamp > env.block.time.seconds() {
-        return Ok(OracleSampleAtResponse {
-            sample: OracleSampleResponse {
-                cumulative_id: 0,
-                cumulative_volatility: 0,
-                cumulative_bin_crossed: 0,
-            },
+        return Ok(OracleSampleResponse {
+            cumulative_id: 0,
+            cumulative_volatility: 0,
+            cumulative_bin_crossed: 0,
         });
     }
 
@@ -362,12 +618,10 @@ pub fn get_oracle_sample_at(
         cumulative_volatility += parameters.get_volatility_accumulator() as u64 * delta_time;
     }
 
-    Ok(OracleSampleAtResponse {
-        sample: OracleSampleResponse {
-            cumulative_id,
-            cumulative_volatility,
-            cumulative_bin_crossed,
-        },
+    Ok(OracleSampleResponse {
+        cumulative_id,
+        cumulative_volatility,
+        cumulative_bin_crossed,
     })
 }
 
@@ -402,16 +656,23 @@ pub fn get_id_from_price(deps: Deps, price: Uint256) -> Result<IdFromPriceRespon
 ///
 /// * `amount_out` - The amount of token X or Y to swap in
 /// * `swap_for_y` - Whether the swap is for token Y (true) or token X (false)
+/// * `id_limit` - The id of the last bin the swap may use, which the active id must not be past
+/// * `at_timestamp` - The time at which to simulate the swap, defaults to the current block time
+/// * `volatility_accumulator` - Overrides the current volatility accumulator
 ///
 /// # Returns
 /// * `amount_in` - The amount of token X or Y that can be swapped in, including the fee
 /// * `amount_out_left` - The amount of token Y or X that cannot be swapped out
 /// * `fee` - The fee of the swap
+/// * `bin_fees` - The base, variable and total fee used in each crossed bin
 pub fn get_swap_in(
     deps: Deps,
     env: Env,
     amount_out: u128,
     swap_for_y: bool,
+    id_limit: Option<u32>,
+    at_timestamp: Option<u64>,
+    volatility_accumulator: Option<u32>,
 ) -> Result<SwapInResponse> {
     let mut amount_in = 0u128;
     let mut amount_out_left = amount_out;
@@ -420,13 +681,21 @@ pub fn get_swap_in(
     let bin_step = BIN_STEP.load(deps.storage)?;
     // let tree = BIN_TREE.load(deps.storage)?;
 
-    let mut parameters = PARAMETERS.load(deps.storage)?;
+    let mut parameters =
+        _get_simulation_parameters(deps, &env, at_timestamp, volatility_accumulator)?;
 
     let mut id = parameters.get_active_id();
+    let mut bin_fees = Vec::new();
 
-    parameters.update_references(env.block.time.seconds())?;
+    // Fails like the swap would, rather than quoting nothing.
+    if is_past_id_limit(swap_for_y, id, id_limit) {
+        return Err(Error::InvalidIdLimit {
+            id_limit: id_limit.unwrap_or_default(),
+            active_id: id,
+        });
+    }
 
-    loop {
+    while !is_past_id_limit(swap_for_y, id, id_limit) {
         let bin_reserves = BINS
             .get(deps.storage, &id)
             .unwrap_or_default()
@@ -453,6 +722,8 @@ pub fn get_swap_in(
             let total_fee = parameters.get_total_fee(bin_step)?;
             let fee_amount = amount_in_without_fee.get_fee_amount(total_fee)?;
 
+            bin_fees.push(_get_bin_fee(&parameters, bin_step, id)?);
+
             amount_in += amount_in_without_fee + fee_amount;
             amount_out_left -= amount_out_of_bin;
 
@@ -476,6 +747,7 @@ pub fn get_swap_in(
         amount_in: Uint128::from(amount_in),
         amount_out_left: Uint128::from(amount_out_left),
         fee: Uint128::from(fee),
+        bin_fees,
     })
 }
 
@@ -490,16 +762,23 @@ pub fn get_swap_in(
 ///
 /// * `amount_in` - The amount of token X or Y to swap in
 /// * `swap_for_y` - Whether the swap is for token Y (true) or token X (false)
+/// * `id_limit` - The id of the last bin the swap may use, which the active id must not be past
+/// * `at_timestamp` - The time at which to simulate the swap, defaults to the current block time
+/// * `volatility_accumulator` - Overrides the current volatility accumulator
 ///
 /// # Returns
 /// * `amount_in_left` - The amount of token X or Y that cannot be swapped in
 /// * `amount_out` - The amount of token Y or X that can be swapped out
 /// * `fee` - The fee of the swap
+/// * `bin_fees` - The base, variable and total fee used in each crossed bin
 pub fn get_swap_out(
     deps: Deps,
     env: Env,
     amount_in: u128,
     swap_for_y: bool,
+    id_limit: Option<u32>,
+    at_timestamp: Option<u64>,
+    volatility_accumulator: Option<u32>,
 ) -> Result<SwapOutResponse> {
     let mut amounts_in_left = Bytes32::encode_alt(amount_in, swap_for_y);
     let mut amounts_out = 0u128;
@@ -508,13 +787,21 @@ pub fn get_swap_out(
     let bin_step = BIN_STEP.load(deps.storage)?;
     // let tree = BIN_TREE.load(deps.storage)?;
 
-    let mut parameters = PARAMETERS.load(deps.storage)?;
+    let mut parameters =
+        _get_simulation_parameters(deps, &env, at_timestamp, volatility_accumulator)?;
 
     let mut id = parameters.get_active_id();
+    let mut bin_fees = Vec::new();
 
-    parameters.update_references(env.block.time.seconds())?;
+    // Fails like the swap would, rather than quoting nothing.
+    if is_past_id_limit(swap_for_y, id, id_limit) {
+        return Err(Error::InvalidIdLimit {
+            id_limit: id_limit.unwrap_or_default(),
+            active_id: id,
+        });
+    }
 
-    loop {
+    while !is_past_id_limit(swap_for_y, id, id_limit) {
         let bin_reserves = BINS.get(deps.storage, &id).unwrap_or_default();
         if !bin_reserves.is_empty(!swap_for_y) {
             parameters.update_volatility_accumulator(id)?;
@@ -526,6 +813,8 @@ pub fn get_swap_out(
                 amounts_in_left = amounts_in_left.sub(amounts_in_with_fees)?;
                 amounts_out += amounts_out_of_bin.decode_alt(!swap_for_y);
                 fee += total_fees.decode_alt(swap_for_y);
+
+                bin_fees.push(_get_bin_fee(&parameters, bin_step, id)?);
             }
         }
 
@@ -548,6 +837,49 @@ pub fn get_swap_out(
         amount_in_left: Uint128::from(amount_in_left),
         amount_out: Uint128::from(amounts_out),
         fee: Uint128::from(fee),
+        bin_fees,
+    })
+}
+
+/// Returns the pair parameters with their references updated as they would be at `at_timestamp`.
+/// If provided, `volatility_accumulator` replaces the volatility reference the swap starts from,
+/// so that the bins crossed by the simulation accumulate on top of it.
+fn _get_simulation_parameters(
+    deps: Deps,
+    env: &Env,
+    at_timestamp: Option<u64>,
+    volatility_accumulator: Option<u32>,
+) -> Result<PairParameters> {
+    let mut parameters = PARAMETERS.load(deps.storage)?;
+
+    let at_timestamp = at_timestamp.unwrap_or(env.block.time.seconds());
+    let time_of_last_update = parameters.get_time_of_last_update();
+
+    if at_timestamp < time_of_last_update {
+        return Err(Error::InvalidSimulationTimestamp {
+            at_timestamp,
+            time_of_last_update,
+        });
+    }
+
+    parameters.update_references(at_timestamp)?;
+
+    if let Some(volatility_accumulator) = volatility_accumulator {
+        parameters
+            .set_volatility_reference(volatility_accumulator)?
+            .set_volatility_accumulator(volatility_accumulator)?;
+    }
+
+    Ok(parameters)
+}
+
+/// Returns the fees the `parameters` apply in the bin `id`.
+fn _get_bin_fee(parameters: &PairParameters, bin_step: u16, id: u32) -> Result<BinFeeResponse> {
+    Ok(BinFeeResponse {
+        bin_id: id,
+        base_fee: Uint128::from(parameters.get_base_fee(bin_step)),
+        variable_fee: Uint128::from(parameters.get_variable_fee(bin_step)),
+        total_fee: Uint128::from(parameters.get_total_fee(bin_step)?),
     })
 }
 
@@ -565,3 +897,312 @@ pub fn get_total_supply(deps: Deps, id: u32) -> Result<LbTokenSupplyResponse> {
 
     Ok(LbTokenSupplyResponse { total_supply })
 }
+
+/// Returns a page of the bins in which `account` has liquidity, with its shares and the
+/// amounts of tokens X and Y those shares can be redeemed for.
+pub fn get_user_bins(
+    deps: Deps,
+    account: String,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<UserBinsResponse> {
+    let page = page.unwrap_or(0);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_USER_BINS_PAGE_SIZE)
+        .min(MAX_USER_BINS_PAGE_SIZE);
+
+    let (ids, total) = user_bins(deps, &account, page, page_size)?;
+
+    let mut bins = Vec::with_capacity(ids.len());
+
+    for id in ids {
+        let shares = balance_of(deps, account.clone(), id);
+        let supply = total_supply(deps, id);
+
+        let (amount_x, amount_y) = BINS
+            .get(deps.storage, &id)
+            .unwrap_or_default()
+            .get_amount_out_of_bin(shares.uint256_to_u256(), supply.uint256_to_u256())?
+            .decode();
+
+        bins.push(UserBinResponse {
+            bin_id: id,
+            shares,
+            amount_x: Uint128::from(amount_x),
+            amount_y: Uint128::from(amount_y),
+        });
+    }
+
+    Ok(UserBinsResponse { bins, total })
+}
+
+/// Returns the fees earned by `account` in each of the bins `ids` since its first deposit, and
+/// the sum over those bins.
+///
+/// # Arguments
+///
+/// * `account` - The account
+/// * `ids` - The ids of the bins, at most `MAX_USER_BINS_PAGE_SIZE`, or a page of the bins in
+///   which the account has shares if `None`
+/// * `page` - The page of the account's bins, if `ids` is `None`
+/// * `page_size` - The number of bins per page, if `ids` is `None`
+pub fn get_accrued_fees(
+    deps: Deps,
+    account: String,
+    ids: Option<Vec<u32>>,
+    page: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<AccruedFeesResponse> {
+    let ids = match ids {
+        Some(ids) if ids.len() > MAX_USER_BINS_PAGE_SIZE as usize => {
+            return Err(Error::TooManyIds {
+                count: ids.len(),
+                max: MAX_USER_BINS_PAGE_SIZE as usize,
+            });
+        }
+        Some(ids) => ids,
+        None => {
+            let page_size = page_size
+                .unwrap_or(DEFAULT_USER_BINS_PAGE_SIZE)
+                .min(MAX_USER_BINS_PAGE_SIZE);
+
+            user_bins(deps, &account, page.unwrap_or(0), page_size)?.0
+        }
+    };
+
+    let mut bins = Vec::with_capacity(ids.len());
+    let mut total_fees_x = Uint128::zero();
+    let mut total_fees_y = Uint128::zero();
+
+    for id in ids {
+        let (fees_x, fees_y) = accrued_fees(deps.storage, &account, id)?;
+
+        total_fees_x += Uint128::from(fees_x);
+        total_fees_y += Uint128::from(fees_y);
+
+        bins.push(AccruedFeesBinResponse {
+            bin_id: id,
+            fees_x: Uint128::from(fees_x),
+            fees_y: Uint128::from(fees_y),
+        });
+    }
+
+    Ok(AccruedFeesResponse {
+        bins,
+        total_fees_x,
+        total_fees_y,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{
+        testing::{mock_dependencies, mock_env},
+        Storage,
+    };
+    use liquidity_book::testing::ACTIVE_ID;
+
+    fn add_bin(storage: &mut dyn Storage, id: u32, reserves: Bytes32) {
+        BINS.insert(storage, &id, &reserves).unwrap();
+        TREE.add(storage, id).unwrap();
+    }
+
+    fn bin_ids(response: &BinsInRangeResponse) -> Vec<u32> {
+        response.bins.iter().map(|bin| bin.bin_id).collect()
+    }
+
+    #[test]
+    fn test_simulation_volatility_accumulator_override() {
+        let mut deps = mock_dependencies();
+        let bin_step = 20u16;
+        let active_id = 8388608u32;
+
+        let mut parameters = PairParameters::default();
+        parameters
+            .set_static_fee_parameters(5000, 30, 600, 5000, 40000, 1000, 350000)
+            .unwrap()
+            .set_active_id(active_id)
+            .unwrap();
+        PARAMETERS.save(deps.as_mut().storage, &parameters).unwrap();
+
+        let env = mock_env();
+
+        let mut parameters = _get_simulation_parameters(deps.as_ref(), &env, None, None).unwrap();
+        parameters.update_volatility_accumulator(active_id).unwrap();
+        let fee = _get_bin_fee(&parameters, bin_step, active_id).unwrap();
+
+        let mut parameters =
+            _get_simulation_parameters(deps.as_ref(), &env, None, Some(100_000)).unwrap();
+        parameters.update_volatility_accumulator(active_id).unwrap();
+        let fee_with_override = _get_bin_fee(&parameters, bin_step, active_id).unwrap();
+
+        assert_eq!(fee.variable_fee, Uint128::zero());
+        assert!(fee_with_override.variable_fee > Uint128::zero());
+        assert!(fee_with_override.total_fee > fee.total_fee);
+    }
+
+    #[test]
+    fn test_accrued_fees_rejects_too_many_ids() {
+        let deps = mock_dependencies();
+        let ids = (0..=MAX_USER_BINS_PAGE_SIZE).collect();
+
+        let err = get_accrued_fees(deps.as_ref(), "alice".to_string(), Some(ids), None, None)
+            .unwrap_err();
+
+        assert!(matches!(err, Error::TooManyIds { .. }));
+    }
+
+    #[test]
+    fn test_swap_quotes_reject_past_id_limit() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let mut parameters = PairParameters::default();
+        parameters.set_active_id(ACTIVE_ID).unwrap();
+        PARAMETERS.save(deps.as_mut().storage, &parameters).unwrap();
+        BIN_STEP.save(deps.as_mut().storage, &20).unwrap();
+
+        let err = get_swap_out(
+            deps.as_ref(),
+            env.clone(),
+            1000,
+            true,
+            Some(ACTIVE_ID + 1),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidIdLimit { .. }));
+
+        let err = get_swap_in(
+            deps.as_ref(),
+            env,
+            1000,
+            false,
+            Some(ACTIVE_ID - 1),
+            None,
+            None,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::InvalidIdLimit { .. }));
+    }
+
+    #[test]
+    fn test_bins_in_range_cursor_and_direction() {
+        let mut deps = mock_dependencies();
+        BIN_STEP.save(deps.as_mut().storage, &20).unwrap();
+        for id in [ACTIVE_ID - 2, ACTIVE_ID, ACTIVE_ID + 3] {
+            add_bin(deps.as_mut().storage, id, Bytes32::encode(100, 100));
+        }
+
+        let page =
+            get_bins_in_range(deps.as_ref(), None, None, Direction::Ascending, Some(2)).unwrap();
+        assert_eq!(bin_ids(&page), vec![ACTIVE_ID - 2, ACTIVE_ID]);
+        assert_eq!(page.next_id, Some(ACTIVE_ID + 3));
+
+        let page = get_bins_in_range(
+            deps.as_ref(),
+            page.next_id,
+            None,
+            Direction::Ascending,
+            Some(2),
+        )
+        .unwrap();
+        assert_eq!(bin_ids(&page), vec![ACTIVE_ID + 3]);
+        assert_eq!(page.next_id, None);
+
+        let page =
+            get_bins_in_range(deps.as_ref(), None, None, Direction::Descending, Some(2)).unwrap();
+        assert_eq!(bin_ids(&page), vec![ACTIVE_ID + 3, ACTIVE_ID]);
+        assert_eq!(page.next_id, Some(ACTIVE_ID - 2));
+
+        // The bounds are inclusive, and an empty start id begins at the next non-empty bin.
+        let page = get_bins_in_range(
+            deps.as_ref(),
+            Some(ACTIVE_ID + 2),
+            Some(ACTIVE_ID - 2),
+            Direction::Descending,
+            None,
+        )
+        .unwrap();
+        assert_eq!(bin_ids(&page), vec![ACTIVE_ID, ACTIVE_ID - 2]);
+        assert_eq!(page.next_id, None);
+
+        let page = get_bins_in_range(
+            deps.as_ref(),
+            Some(ACTIVE_ID - 1),
+            Some(ACTIVE_ID + 2),
+            Direction::Ascending,
+            None,
+        )
+        .unwrap();
+        assert_eq!(bin_ids(&page), vec![ACTIVE_ID]);
+        assert_eq!(page.next_id, None);
+    }
+
+    #[test]
+    fn test_liquidity_depth_counts_active_bin_once() {
+        let mut deps = mock_dependencies();
+        let mut parameters = PairParameters::default();
+        parameters.set_active_id(ACTIVE_ID).unwrap();
+        PARAMETERS.save(deps.as_mut().storage, &parameters).unwrap();
+        BIN_STEP.save(deps.as_mut().storage, &20).unwrap();
+
+        add_bin(
+            deps.as_mut().storage,
+            ACTIVE_ID - 1,
+            Bytes32::encode(0, 100),
+        );
+        add_bin(deps.as_mut().storage, ACTIVE_ID, Bytes32::encode(50, 50));
+        add_bin(
+            deps.as_mut().storage,
+            ACTIVE_ID + 1,
+            Bytes32::encode(100, 0),
+        );
+        add_bin(
+            deps.as_mut().storage,
+            ACTIVE_ID + 2,
+            Bytes32::encode(100, 0),
+        );
+        // Outside of the radius.
+        add_bin(
+            deps.as_mut().storage,
+            ACTIVE_ID + 3,
+            Bytes32::encode(100, 0),
+        );
+
+        let response = get_liquidity_depth(deps.as_ref(), 2).unwrap();
+
+        let depth: Vec<_> = response
+            .bins
+            .iter()
+            .map(|bin| (bin.bin_id, bin.cumulative_x.u128(), bin.cumulative_y.u128()))
+            .collect();
+        assert_eq!(response.active_id, ACTIVE_ID);
+        assert_eq!(
+            depth,
+            vec![
+                (ACTIVE_ID - 1, 50, 150),
+                (ACTIVE_ID, 50, 50),
+                (ACTIVE_ID + 1, 100, 0),
+                (ACTIVE_ID + 2, 200, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_twap_requires_oracle() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut parameters = PairParameters::default();
+        parameters.set_active_id(ACTIVE_ID).unwrap();
+        PARAMETERS.save(deps.as_mut().storage, &parameters).unwrap();
+
+        let err = get_twap(deps.as_ref(), env.clone(), 100, 100).unwrap_err();
+        assert!(matches!(err, Error::InvalidTwapWindow { .. }));
+
+        let err = get_twap(deps.as_ref(), env, 100, 0).unwrap_err();
+        assert!(matches!(err, Error::OracleNotInitialized));
+    }
+}
@@ -1,13 +1,15 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, ContractInfo, StdResult, Storage};
+use cosmwasm_std::{Addr, ContractInfo, StdResult, Storage, Uint128, Uint256};
 use ethnum::U256;
 use liquidity_book::{
     core::TokenType,
-    interfaces::{lb_factory::ILbFactory, lb_pair::ContractStatus},
+    interfaces::{
+        lb_factory::ILbFactory, lb_pair::ContractStatus, lb_token::expiration::Expiration,
+    },
     libraries::{
         hooks::HooksParameters,
         math::{bit_math::BitMath, u24::U24},
-        Bytes32, OracleSample, PairParameters,
+        Bytes32, LiquidityConfigurations, OracleSample, PairParameters,
     },
 };
 use secret_toolkit::{
@@ -15,9 +17,8 @@ use secret_toolkit::{
     storage::{Item, Keymap, KeymapBuilder, WithoutIter},
 };
 
-pub static STATE: Item<State> = Item::new(b"state");
-
 pub static CONTRACT_STATUS: Item<ContractStatus, Json> = Item::new(b"contract_status");
+pub static PAUSED_OPERATIONS: Item<u8> = Item::new(b"paused_operations");
 pub static VIEWING_KEY: Item<String> = Item::new(b"contract_viewing_key");
 
 pub static FACTORY: Item<ILbFactory> = Item::new(b"lb_factory");
@@ -39,14 +40,53 @@ pub static RESERVES: Item<Bytes32> = Item::new(b"reserves");
 pub static PROTOCOL_FEES: Item<Bytes32> = Item::new(b"protocol_fees");
 pub static HOOKS_PARAMETERS: Item<Option<HooksParameters>> = Item::new(b"hooks_parameters");
 
+/// The cumulative LP fees per share of each bin.
+pub static FEES_PER_SHARE: Keymap<u32, FeesPerShare, Bincode2, WithoutIter> =
+    KeymapBuilder::new(b"fees_per_share").without_iter().build();
+/// The mints still waiting for their other token, by account.
+pub static PENDING_MINTS: Keymap<Addr, PendingMint, Bincode2, WithoutIter> =
+    KeymapBuilder::new(b"pending_mints").without_iter().build();
+/// The sum of all `PENDING_MINTS`. These tokens are held by the pair but are not part of the reserves.
+pub static PENDING_MINTS_TOTAL: Item<Bytes32> = Item::new(b"pending_mints_total");
+
 pub static EPHEMERAL_LB_TOKEN: Item<EphemeralLbToken> = Item::new(b"ephemeral_lb_token");
 pub static EPHEMERAL_FLASH_LOAN: Item<EphemeralFlashLoan> = Item::new(b"ephemeral_flash_loan");
 
-// TODO: do we even need this?
+/// The tokens received through SNIP-20 `Send` for a mint still waiting for its other token, and
+/// the parameters of that mint, which every other deposit of the mint must repeat.
+#[cw_serde]
+pub struct PendingMint {
+    pub amounts: Bytes32,
+    pub to: String,
+    pub liquidity_configs: Vec<LiquidityConfigurations>,
+    pub refund_to: String,
+}
+
+/// Cumulative fees per share, as 128.128-binary fixed-point numbers.
+///
+/// The values are allowed to overflow: only the difference between two snapshots is meaningful.
+#[cw_serde]
+#[derive(Default)]
+pub struct FeesPerShare {
+    pub x: Uint256,
+    pub y: Uint256,
+}
+
+/// The fees per share of a bin at the last balance change of an account, and the fees that
+/// account had accrued in the bin up to that point.
+#[cw_serde]
+#[derive(Default)]
+pub struct FeeCheckpoint {
+    pub fees_per_share: FeesPerShare,
+    pub accrued_x: Uint128,
+    pub accrued_y: Uint128,
+}
+
+/// The shares of a bin that a spender can transfer on behalf of an account.
 #[cw_serde]
-pub struct State {
-    pub creator: Addr,
-    pub admin_auth: ContractInfo,
+pub struct Allowance {
+    pub amount: Uint256,
+    pub expiration: Expiration,
 }
 
 #[cw_serde]
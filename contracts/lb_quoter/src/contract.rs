@@ -4,10 +4,16 @@ use crate::{
     Error, Result,
 };
 use cosmwasm_std::{entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
-use liquidity_book::interfaces::lb_quoter::{
-    FactoryV2_2Response, InstantiateMsg, QueryMsg, RouterV2_2Response,
+use liquidity_book::{
+    core::contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+    interfaces::lb_quoter::{
+        FactoryV2_2Response, InstantiateMsg, MigrateMsg, QueryMsg, RouterV2_2Response,
+    },
 };
 
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[entry_point]
 pub fn instantiate(
     deps: DepsMut,
@@ -27,10 +33,18 @@ pub fn instantiate(
 
     FACTORY_V2_2.save(deps.storage, &factory_v2_2)?;
     ROUTER_V2_2.save(deps.storage, &router_v2_2)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new())
 }
 
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
 // TODO: see what happens if I remove this
 // #[entry_point]
 // pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> Result<()> {
@@ -9,7 +9,7 @@ mod helper;
 mod query;
 mod state;
 
-pub use contract::{instantiate, query};
+pub use contract::{instantiate, migrate, query};
 
 // Use this crate's custom Error type
 pub use liquidity_book::interfaces::lb_quoter::LbQuoterError as Error;
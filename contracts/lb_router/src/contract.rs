@@ -6,7 +6,10 @@ use cosmwasm_std::{
     Reply, Response, StdResult, SubMsgResult, Uint128,
 };
 use liquidity_book::{
-    core::RawContract,
+    core::{
+        contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+        RawContract,
+    },
     interfaces::{
         lb_factory::ILbFactory,
         lb_pair,
@@ -27,9 +30,13 @@ pub const PUBLIC_VIEWING_KEY: &str = "lb_rocks";
 pub const CREATE_LB_PAIR_REPLY_ID: u64 = 1u64;
 pub const MINT_REPLY_ID: u64 = 2u64;
 pub const BURN_REPLY_ID: u64 = 3u64;
+pub const CREATE_LB_PAIR_AND_ADD_LIQUIDITY_REPLY_ID: u64 = 4u64;
 pub const SWAP_REPLY_ID: u64 = 10u64;
 pub const SWAP_FOR_EXACT_REPLY_ID: u64 = 11u64;
 
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // TODO: Need to be able to query the factory contract to check who the owner/admin is.
 // This could either be at the chain level ("admin") or stored internally in the factory contract.
 pub fn only_factory_owner(deps: Deps, env: Env, info: MessageInfo) -> Result<()> {
@@ -77,6 +84,7 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
     FACTORY_V2_2.save(deps.storage, &ILbFactory(msg.factory))?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     // TODO: Register existing tokens with the router contract. If we ever deploy a new
     // router, we'll need a way to register all of the tokens used by the pairs.
@@ -257,6 +265,10 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> R
         // not in joe-v2
         ExecuteMsg::Register { address, code_hash } => register(deps, env, address, code_hash),
         ExecuteMsg::RegisterBatch { tokens } => register_batch(deps, env, tokens),
+        ExecuteMsg::CreateLbPairAndAddLiquidity {
+            active_id,
+            liquidity_parameters,
+        } => create_lb_pair_and_add_liquidity(deps, env, info, active_id, liquidity_parameters),
         ExecuteMsg::Receive {
             sender,
             from,
@@ -324,6 +336,42 @@ pub fn receive(
     execute(deps, env, info, msg)
 }
 
+/// Sets the router's viewing key and registers the router to receive each SNIP-20 token of a new
+/// pair.
+fn register_lb_pair_tokens(env: &Env, lb_pair: &lb_pair::LbPair) -> StdResult<Vec<CosmosMsg>> {
+    let mut msgs: Vec<CosmosMsg> = Vec::with_capacity(4);
+
+    for token in [&lb_pair.token_x, &lb_pair.token_y] {
+        if token.is_custom_token() {
+            msgs.extend([
+                snip20::set_viewing_key_msg(
+                    "hola".to_string(),
+                    None,
+                    1,
+                    token.code_hash(),
+                    token.address().to_string(),
+                )?,
+                snip20::register_receive_msg(
+                    env.contract.code_hash.clone(),
+                    None,
+                    1,
+                    token.code_hash(),
+                    token.address().to_string(),
+                )?,
+            ])
+        }
+    }
+
+    Ok(msgs)
+}
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
 #[entry_point]
 pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
     match (msg.id, msg.result) {
@@ -331,30 +379,9 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
             Some(data) => {
                 let lb_pair: lb_pair::LbPair = from_binary(&data)?;
 
-                let data = CreateLbPairResponse { lb_pair };
+                let msgs = register_lb_pair_tokens(&env, &lb_pair)?;
 
-                let mut msgs: Vec<CosmosMsg> = Vec::with_capacity(2);
-
-                for token in [&data.lb_pair.token_x, &data.lb_pair.token_y] {
-                    if token.is_custom_token() {
-                        msgs.extend([
-                            snip20::set_viewing_key_msg(
-                                "hola".to_string(),
-                                None,
-                                1,
-                                token.code_hash(),
-                                token.address().to_string(),
-                            )?,
-                            snip20::register_receive_msg(
-                                env.contract.code_hash.clone(),
-                                None,
-                                1,
-                                token.code_hash(),
-                                token.address().to_string(),
-                            )?,
-                        ])
-                    }
-                }
+                let data = CreateLbPairResponse { lb_pair };
 
                 Ok(Response::new()
                     .set_data(to_binary(&data)?)
@@ -362,6 +389,21 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
             }
             None => Err(Error::ReplyDataMissing),
         },
+        (CREATE_LB_PAIR_AND_ADD_LIQUIDITY_REPLY_ID, SubMsgResult::Ok(s)) => match s.data {
+            Some(data) => {
+                let lb_pair: lb_pair::LbPair = from_binary(&data)?;
+
+                let msgs = register_lb_pair_tokens(&env, &lb_pair)?;
+
+                add_liquidity_to_created_lb_pair(
+                    deps,
+                    env,
+                    Response::new().add_messages(msgs),
+                    lb_pair,
+                )
+            }
+            None => Err(Error::ReplyDataMissing),
+        },
         (MINT_REPLY_ID, SubMsgResult::Ok(s)) => match s.data {
             Some(data) => {
                 let lb_pair::MintResponse {
@@ -374,6 +416,7 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
                     amount_x_min,
                     amount_y_min,
                     deposit_ids,
+                    created_lb_pair,
                 } = EPHEMERAL_ADD_LIQUIDITY.load(deps.storage)?;
 
                 let amounts_added = amounts_received.sub(amounts_left)?;
@@ -402,7 +445,17 @@ pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response> {
                     liquidity_minted,
                 };
 
-                Ok(Response::new().set_data(to_binary(&data)?))
+                // The data of this reply replaces the data of the whole transaction, so it has to
+                // include the pair when it was created in the same transaction.
+                let data = match created_lb_pair {
+                    Some(lb_pair) => to_binary(&CreateLbPairAndAddLiquidityResponse {
+                        lb_pair,
+                        add_liquidity: data,
+                    })?,
+                    None => to_binary(&data)?,
+                };
+
+                Ok(Response::new().set_data(data))
             }
             None => Err(Error::ReplyDataMissing),
         },
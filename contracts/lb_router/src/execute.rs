@@ -6,7 +6,7 @@ use cosmwasm_std::{
 use liquidity_book::{
     core::TokenType,
     interfaces::{
-        lb_pair::ILbPair,
+        lb_pair::{ILbPair, LbPair},
         lb_router::{LiquidityParameters, Path, Version},
     },
     libraries::{math::u24::U24, LiquidityConfigurations},
@@ -38,6 +38,62 @@ pub fn create_lb_pair(
     Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, CREATE_LB_PAIR_REPLY_ID)))
 }
 
+/// Create a liquidity bin LBPair using the factory, then add liquidity to it in the same
+/// transaction.
+///
+/// The pair is created with the tokens and bin step of the liquidity parameters.
+/// Only SNIP-20 tokens are supported, as the liquidity is transferred with `transfer_from`.
+pub fn create_lb_pair_and_add_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    active_id: u32,
+    liquidity_parameters: LiquidityParameters,
+) -> Result<Response> {
+    ensure(&env, liquidity_parameters.deadline.u64())?;
+
+    if let Some(coin) = info.funds.first() {
+        return Err(Error::NativeTokenNotSupported {
+            denom: coin.denom.clone(),
+        });
+    }
+    for token in [&liquidity_parameters.token_x, &liquidity_parameters.token_y] {
+        if let TokenType::NativeToken { denom } = token {
+            return Err(Error::NativeTokenNotSupported {
+                denom: denom.clone(),
+            });
+        }
+    }
+
+    let entropy = env.block.random.unwrap_or(to_binary(b"meh")?); // TODO:
+
+    let factory = FACTORY_V2_2.load(deps.storage)?;
+
+    let msg = factory.create_lb_pair(
+        liquidity_parameters.token_x.clone(),
+        liquidity_parameters.token_y.clone(),
+        active_id,
+        liquidity_parameters.bin_step,
+        PUBLIC_VIEWING_KEY.to_string(),
+        entropy.to_string(),
+    )?;
+
+    // NOTE: See reply in contract.rs for continuation of this function.
+
+    EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY.save(
+        deps.storage,
+        &EphemeralCreateLbPairAndAddLiquidity {
+            sender: info.sender,
+            liquidity_parameters,
+        },
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+        msg,
+        CREATE_LB_PAIR_AND_ADD_LIQUIDITY_REPLY_ID,
+    )))
+}
+
 /// Add liquidity while performing safety checks.
 ///
 /// This function is compliant with fee on transfer tokens.
@@ -64,25 +120,52 @@ pub fn add_liquidity(
     // sender would be this contract, not the user.
 
     // Transfer tokens from sender to the pair contract.
-    let transfer_from_x = _safe_transfer_from(
-        &liquidity_parameters.token_x,
+    let response = _transfer_liquidity_from(
+        Response::new(),
+        &liquidity_parameters,
         &info.sender,
         &lb_pair.0.address,
-        liquidity_parameters.amount_x,
     )?;
-    let transfer_from_y = _safe_transfer_from(
-        &liquidity_parameters.token_y,
-        &info.sender,
-        &lb_pair.0.address,
-        liquidity_parameters.amount_y,
+
+    _add_liquidity(deps, env, response, liquidity_parameters, lb_pair, None)
+}
+
+/// Continuation of `create_lb_pair_and_add_liquidity`, once the factory created the pair.
+pub fn add_liquidity_to_created_lb_pair(
+    deps: DepsMut,
+    env: Env,
+    response: Response,
+    lb_pair: LbPair,
+) -> Result<Response> {
+    let EphemeralCreateLbPairAndAddLiquidity {
+        sender,
+        liquidity_parameters,
+    } = EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY.load(deps.storage)?;
+    EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY.remove(deps.storage);
+
+    // The factory creates the pair with the tokens in the given order.
+    if liquidity_parameters.token_x != lb_pair.token_x {
+        return Err(Error::WrongTokenOrder);
+    }
+
+    // Transfer tokens from sender to the pair contract.
+    let response = _transfer_liquidity_from(
+        response,
+        &liquidity_parameters,
+        &sender,
+        &lb_pair.contract.address,
     )?;
 
-    let response = [transfer_from_x, transfer_from_y]
-        .into_iter()
-        .flatten()
-        .fold(Response::new(), |resp, msg| resp.add_message(msg));
+    let pair = ILbPair(lb_pair.contract.clone());
 
-    _add_liquidity(deps, env, response, liquidity_parameters, lb_pair)
+    _add_liquidity(
+        deps,
+        env,
+        response,
+        liquidity_parameters,
+        pair,
+        Some(lb_pair),
+    )
 }
 
 /// Add liquidity with NATIVE while performing safety checks.
@@ -466,6 +549,7 @@ fn _add_liquidity(
     response: Response,
     liq: LiquidityParameters,
     pair: ILbPair,
+    created_lb_pair: Option<LbPair>,
 ) -> Result<Response> {
     ensure(&env, liq.deadline.u64())?;
 
@@ -517,6 +601,7 @@ fn _add_liquidity(
             amount_x_min: liq.amount_x_min,
             amount_y_min: liq.amount_y_min,
             deposit_ids,
+            created_lb_pair,
         },
     )?;
 
@@ -527,6 +612,22 @@ fn _add_liquidity(
     Ok(response)
 }
 
+/// Helper function to transfer the liquidity amounts from `from` to the pair.
+fn _transfer_liquidity_from(
+    response: Response,
+    liq: &LiquidityParameters,
+    from: &Addr,
+    lb_pair: &Addr,
+) -> StdResult<Response> {
+    let transfer_from_x = _safe_transfer_from(&liq.token_x, from, lb_pair, liq.amount_x)?;
+    let transfer_from_y = _safe_transfer_from(&liq.token_y, from, lb_pair, liq.amount_y)?;
+
+    Ok([transfer_from_x, transfer_from_y]
+        .into_iter()
+        .flatten()
+        .fold(response, |resp, msg| resp.add_message(msg)))
+}
+
 /// Helper function to return the amounts in.
 fn _get_amounts_in(
     deps: Deps,
@@ -624,7 +725,7 @@ pub(crate) fn _swap_exact_tokens_for_tokens(
                 Ok(data)
             })?;
 
-            let lb_pair_swap_msg = pair.swap(swap_for_y, recipient.to_string())?;
+            let lb_pair_swap_msg = pair.swap(swap_for_y, recipient.to_string(), None)?;
             let response =
                 response.add_submessage(SubMsg::reply_on_success(lb_pair_swap_msg, SWAP_REPLY_ID));
 
@@ -676,7 +777,7 @@ pub(crate) fn _swap_tokens_for_exact_tokens(
                 Ok(data)
             })?;
 
-            let lb_pair_swap_msg = pair.swap(swap_for_y, recipient.to_string())?;
+            let lb_pair_swap_msg = pair.swap(swap_for_y, recipient.to_string(), None)?;
             let response = response.add_submessage(SubMsg::reply_on_success(
                 lb_pair_swap_msg,
                 SWAP_FOR_EXACT_REPLY_ID,
@@ -876,3 +977,110 @@ fn _w_native_withdraw_and_transfer(
 
     Ok(Some(vec![withdraw, transfer.into()]))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::{
+        coins,
+        testing::{mock_dependencies, mock_env, mock_info},
+        ContractResult, SystemResult,
+    };
+    use liquidity_book::{
+        interfaces::lb_pair::ActiveIdResponse,
+        testing::{mock_lb_pair, mock_token, ACTIVE_ID, BIN_STEP},
+    };
+
+    fn liquidity_parameters(token_x: TokenType, token_y: TokenType) -> LiquidityParameters {
+        LiquidityParameters {
+            token_x,
+            token_y,
+            bin_step: BIN_STEP,
+            amount_x: Uint128::new(1_000),
+            amount_y: Uint128::new(1_000),
+            amount_x_min: Uint128::zero(),
+            amount_y_min: Uint128::zero(),
+            active_id_desired: ACTIVE_ID,
+            id_slippage: 0,
+            delta_ids: vec![0],
+            distribution_x: vec![Uint64::new(1_000_000_000_000_000_000)],
+            distribution_y: vec![Uint64::new(1_000_000_000_000_000_000)],
+            to: "user".to_string(),
+            refund_to: "user".to_string(),
+            deadline: Uint64::new(u64::MAX),
+        }
+    }
+
+    #[test]
+    fn test_create_lb_pair_and_add_liquidity_rejects_native_tokens() {
+        let mut deps = mock_dependencies();
+        let native = TokenType::NativeToken {
+            denom: "uscrt".to_string(),
+        };
+
+        let err = create_lb_pair_and_add_liquidity(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &[]),
+            ACTIVE_ID,
+            liquidity_parameters(mock_token("token_x"), native),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::NativeTokenNotSupported { denom } if denom == "uscrt"));
+
+        let err = create_lb_pair_and_add_liquidity(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("user", &coins(1_000, "uscrt")),
+            ACTIVE_ID,
+            liquidity_parameters(mock_token("token_x"), mock_token("token_y")),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::NativeTokenNotSupported { denom } if denom == "uscrt"));
+    }
+
+    #[test]
+    fn test_add_liquidity_to_created_lb_pair() {
+        let mut deps = mock_dependencies();
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&ActiveIdResponse {
+                    active_id: ACTIVE_ID,
+                })
+                .unwrap(),
+            ))
+        });
+
+        let liquidity_parameters =
+            liquidity_parameters(mock_token("token_x"), mock_token("token_y"));
+        EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY
+            .save(
+                deps.as_mut().storage,
+                &EphemeralCreateLbPairAndAddLiquidity {
+                    sender: Addr::unchecked("user"),
+                    liquidity_parameters: liquidity_parameters.clone(),
+                },
+            )
+            .unwrap();
+
+        let response = add_liquidity_to_created_lb_pair(
+            deps.as_mut(),
+            mock_env(),
+            Response::new(),
+            mock_lb_pair(),
+        )
+        .unwrap();
+
+        // Both tokens are transferred from the sender, then the pair mints in a submessage.
+        assert_eq!(response.messages.len(), 3);
+        assert_eq!(response.messages[2].id, MINT_REPLY_ID);
+
+        assert!(EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY
+            .may_load(deps.as_ref().storage)
+            .unwrap()
+            .is_none());
+        let ephemeral = EPHEMERAL_ADD_LIQUIDITY.load(deps.as_ref().storage).unwrap();
+        assert_eq!(ephemeral.deposit_ids, vec![ACTIVE_ID]);
+        assert!(ephemeral.created_lb_pair.is_some());
+    }
+}
@@ -7,7 +7,7 @@ mod execute;
 mod query;
 mod state;
 
-pub use contract::{execute, instantiate, query, reply};
+pub use contract::{execute, instantiate, migrate, query, reply};
 
 // Use this crate's custom Error type
 pub use liquidity_book::interfaces::lb_router::LbRouterError as Error;
@@ -62,11 +62,15 @@ pub fn get_swap_in(
     let msg = lb_pair::QueryMsg::GetSwapIn {
         amount_out,
         swap_for_y,
+        id_limit: None,
+        at_timestamp: None,
+        volatility_accumulator: None,
     };
     let lb_pair::SwapInResponse {
         amount_in,
         amount_out_left,
         fee,
+        ..
     } = deps.querier.query_wasm_smart::<lb_pair::SwapInResponse>(
         lb_pair.code_hash,
         lb_pair.address.to_string(),
@@ -90,11 +94,15 @@ pub fn get_swap_out(
     let msg = lb_pair::QueryMsg::GetSwapOut {
         amount_in,
         swap_for_y,
+        id_limit: None,
+        at_timestamp: None,
+        volatility_accumulator: None,
     };
     let lb_pair::SwapOutResponse {
         amount_in_left,
         amount_out,
         fee,
+        ..
     } = deps.querier.query_wasm_smart::<lb_pair::SwapOutResponse>(
         lb_pair.code_hash,
         lb_pair.address.to_string(),
@@ -2,13 +2,21 @@ use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, Uint128};
 use liquidity_book::{
     core::TokenType,
-    interfaces::{lb_factory::ILbFactory, lb_pair::ILbPair, lb_router::Version},
+    interfaces::{
+        lb_factory::ILbFactory,
+        lb_pair::{ILbPair, LbPair},
+        lb_router::{LiquidityParameters, Version},
+    },
 };
 use secret_toolkit::{serialization::Json, storage::Item};
 
 pub const FACTORY_V2_2: Item<ILbFactory> = Item::new(b"factory_v2_2");
 
-pub const EPHEMERAL_ADD_LIQUIDITY: Item<EphemeralAddLiquidity> =
+pub const EPHEMERAL_CREATE_LB_PAIR_AND_ADD_LIQUIDITY: Item<
+    EphemeralCreateLbPairAndAddLiquidity,
+    Json,
+> = Item::new(b"ephemeral_create_lb_pair_and_add_liquidity");
+pub const EPHEMERAL_ADD_LIQUIDITY: Item<EphemeralAddLiquidity, Json> =
     Item::new(b"ephemeral_add_liquidity");
 pub const EPHEMERAL_REMOVE_LIQUIDITY: Item<EphemeralRemoveLiquidity> =
     Item::new(b"ephemeral_remove_liquidity");
@@ -16,11 +24,18 @@ pub const EPHEMERAL_SWAP: Item<EphemeralSwap, Json> = Item::new(b"ephemeral_swap
 pub const EPHEMERAL_SWAP_FOR_EXACT: Item<EphemeralSwapForExact, Json> =
     Item::new(b"ephemeral_swap_for_exact");
 
+#[cw_serde]
+pub struct EphemeralCreateLbPairAndAddLiquidity {
+    pub sender: Addr,
+    pub liquidity_parameters: LiquidityParameters,
+}
+
 #[cw_serde]
 pub struct EphemeralAddLiquidity {
     pub amount_x_min: Uint128,
     pub amount_y_min: Uint128,
     pub deposit_ids: Vec<u32>,
+    pub created_lb_pair: Option<LbPair>, // set when the pair was created in the same transaction
 }
 
 #[cw_serde]
@@ -3,9 +3,12 @@ use crate::{execute::*, query::*};
 use cosmwasm_std::{
     entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
 };
-use liquidity_book::interfaces::lb_token::{
-    state_structs::ContractConfig, ExecuteMsg, InstantiateMsg, QueryAnswer, QueryMsg,
-    QueryWithPermit, SendAction,
+use liquidity_book::{
+    core::contract_version::{migrate_contract_version, migrate_response, set_contract_version},
+    interfaces::lb_token::{
+        state_structs::ContractConfig, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryAnswer,
+        QueryMsg, QueryWithPermit, SendAction,
+    },
 };
 use secret_toolkit::{
     crypto::sha_256,
@@ -13,6 +16,9 @@ use secret_toolkit::{
     viewing_key::{ViewingKey, ViewingKeyStore},
 };
 
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /////////////////////////////////////////////////////////////////////////////////
 // Init
 /////////////////////////////////////////////////////////////////////////////////
@@ -66,6 +72,7 @@ pub fn instantiate(
 
     // save contract config -- where tx_cnt would have increased post initial balances
     contr_conf_w(deps.storage).save(&config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let response = Response::new().set_data(to_binary(&env.contract.address)?);
 
     // deps.api
@@ -73,6 +80,17 @@ pub fn instantiate(
     Ok(response)
 }
 
+/////////////////////////////////////////////////////////////////////////////////
+// Migrate
+/////////////////////////////////////////////////////////////////////////////////
+
+#[entry_point]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let previous = migrate_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(migrate_response(previous, CONTRACT_VERSION))
+}
+
 /////////////////////////////////////////////////////////////////////////////////
 // Handles
 /////////////////////////////////////////////////////////////////////////////////
@@ -0,0 +1,261 @@
+//! Owner and role based access control, after OpenZeppelin's `Ownable2Step` and `AccessControl`
+//! as used by joe-v2's LBFactory.
+//!
+//! The owner implicitly has every role, and is the only account that can grant or revoke them.
+//! Ownership is handed over in two steps: the owner proposes a new owner, who has to accept it.
+
+use cosmwasm_std::{Addr, Event, StdResult, Storage};
+use secret_toolkit::storage::{Item, Keyset};
+
+pub const DEFAULT_ADMIN_ROLE: &str = "DEFAULT_ADMIN_ROLE";
+pub const LB_HOOKS_MANAGER_ROLE: &str = "LB_HOOKS_MANAGER_ROLE";
+
+pub static OWNER: Item<Addr> = Item::new(b"owner");
+pub static PENDING_OWNER: Item<Addr> = Item::new(b"pending_owner");
+/// The accounts that were granted each role. Use the role as suffix.
+pub static ROLE_MEMBERS: Keyset<Addr> = Keyset::new(b"role_members");
+
+#[derive(thiserror::Error, Debug)]
+pub enum AccessControlError {
+    #[error("Only the owner can do that!")]
+    OnlyOwner,
+    #[error("Only the pending owner can do that!")]
+    OnlyPendingOwner,
+    #[error("Account {account} is missing role {role}!")]
+    MissingRole { account: Addr, role: String },
+    #[error("Cannot grant default admin role!")]
+    CannotGrantDefaultAdminRole,
+
+    #[error(transparent)]
+    StdError(#[from] cosmwasm_std::StdError),
+}
+
+pub trait AccessControlEventExt {
+    fn ownership_transferred(previous_owner: &Addr, new_owner: &Addr) -> Event {
+        Event::new("ownership_transferred")
+            .add_attribute_plaintext("previous_owner", previous_owner)
+            .add_attribute_plaintext("new_owner", new_owner)
+    }
+
+    fn ownership_transfer_started(previous_owner: &Addr, new_owner: &Addr) -> Event {
+        Event::new("ownership_transfer_started")
+            .add_attribute_plaintext("previous_owner", previous_owner)
+            .add_attribute_plaintext("new_owner", new_owner)
+    }
+
+    fn role_granted(role: &str, account: &Addr, sender: &Addr) -> Event {
+        Event::new("role_granted")
+            .add_attribute_plaintext("role", role)
+            .add_attribute_plaintext("account", account)
+            .add_attribute_plaintext("sender", sender)
+    }
+
+    fn role_revoked(role: &str, account: &Addr, sender: &Addr) -> Event {
+        Event::new("role_revoked")
+            .add_attribute_plaintext("role", role)
+            .add_attribute_plaintext("account", account)
+            .add_attribute_plaintext("sender", sender)
+    }
+}
+
+impl AccessControlEventExt for Event {}
+
+/// Returns the owner of the contract.
+pub fn owner(storage: &dyn Storage) -> StdResult<Addr> {
+    OWNER.load(storage)
+}
+
+/// Returns the pending owner of the contract, if an ownership transfer was started.
+pub fn pending_owner(storage: &dyn Storage) -> StdResult<Option<Addr>> {
+    PENDING_OWNER.may_load(storage)
+}
+
+/// Sets the owner of the contract and deletes the pending owner, without any check.
+pub fn set_owner(storage: &mut dyn Storage, new_owner: &Addr) -> StdResult<Event> {
+    let previous_owner = OWNER
+        .may_load(storage)?
+        .unwrap_or_else(|| Addr::unchecked(""));
+    PENDING_OWNER.remove(storage);
+    OWNER.save(storage, new_owner)?;
+
+    Ok(Event::ownership_transferred(&previous_owner, new_owner))
+}
+
+/// Starts the ownership transfer to `new_owner`. Needs to be called by the owner.
+///
+/// Replaces the pending transfer, if any.
+pub fn transfer_ownership(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    new_owner: &Addr,
+) -> Result<Event, AccessControlError> {
+    only_owner(storage, sender)?;
+
+    PENDING_OWNER.save(storage, new_owner)?;
+
+    Ok(Event::ownership_transfer_started(sender, new_owner))
+}
+
+/// Completes the ownership transfer. Needs to be called by the pending owner.
+pub fn accept_ownership(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+) -> Result<Event, AccessControlError> {
+    if pending_owner(storage)?.as_ref() != Some(sender) {
+        return Err(AccessControlError::OnlyPendingOwner);
+    }
+
+    Ok(set_owner(storage, sender)?)
+}
+
+/// Leaves the contract without owner, like transferring it to the zero address in solidity.
+/// Needs to be called by the owner.
+///
+/// Every function restricted to the owner will no longer be callable.
+pub fn renounce_ownership(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+) -> Result<Event, AccessControlError> {
+    only_owner(storage, sender)?;
+
+    Ok(set_owner(storage, &Addr::unchecked(""))?)
+}
+
+/// Modifier to check that `sender` is the owner.
+pub fn only_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), AccessControlError> {
+    if owner(storage)? != *sender {
+        return Err(AccessControlError::OnlyOwner);
+    }
+
+    Ok(())
+}
+
+/// Returns true if `account` has the `role`, or if it is the owner.
+pub fn has_role(storage: &dyn Storage, role: &str, account: &Addr) -> StdResult<bool> {
+    Ok(owner(storage)? == *account
+        || ROLE_MEMBERS
+            .add_suffix(role.as_bytes())
+            .contains(storage, account))
+}
+
+/// Modifier to check that `account` has the `role`.
+pub fn only_role(
+    storage: &dyn Storage,
+    role: &str,
+    account: &Addr,
+) -> Result<(), AccessControlError> {
+    if !has_role(storage, role, account)? {
+        return Err(AccessControlError::MissingRole {
+            account: account.clone(),
+            role: role.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Grants `role` to `account`. Needs to be called by the owner.
+///
+/// The default admin role can't be granted, as it is reserved for the owner.
+pub fn grant_role(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &str,
+    account: &Addr,
+) -> Result<Event, AccessControlError> {
+    only_owner(storage, sender)?;
+
+    if role == DEFAULT_ADMIN_ROLE {
+        return Err(AccessControlError::CannotGrantDefaultAdminRole);
+    }
+
+    ROLE_MEMBERS
+        .add_suffix(role.as_bytes())
+        .insert(storage, account)?;
+
+    Ok(Event::role_granted(role, account, sender))
+}
+
+/// Revokes `role` from `account`. Needs to be called by the owner.
+pub fn revoke_role(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &str,
+    account: &Addr,
+) -> Result<Event, AccessControlError> {
+    only_owner(storage, sender)?;
+
+    ROLE_MEMBERS
+        .add_suffix(role.as_bytes())
+        .remove(storage, account)?;
+
+    Ok(Event::role_revoked(role, account, sender))
+}
+
+/// Revokes `role` from the `sender`.
+pub fn renounce_role(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &str,
+) -> Result<Event, AccessControlError> {
+    ROLE_MEMBERS
+        .add_suffix(role.as_bytes())
+        .remove(storage, sender)?;
+
+    Ok(Event::role_revoked(role, sender, sender))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn test_roles() {
+        let mut storage = MockStorage::new();
+        let owner = Addr::unchecked("owner");
+        let manager = Addr::unchecked("manager");
+
+        set_owner(&mut storage, &owner).unwrap();
+
+        assert!(has_role(&storage, LB_HOOKS_MANAGER_ROLE, &owner).unwrap());
+        assert!(!has_role(&storage, LB_HOOKS_MANAGER_ROLE, &manager).unwrap());
+
+        assert!(grant_role(&mut storage, &manager, LB_HOOKS_MANAGER_ROLE, &manager).is_err());
+        assert!(grant_role(&mut storage, &owner, DEFAULT_ADMIN_ROLE, &manager).is_err());
+
+        grant_role(&mut storage, &owner, LB_HOOKS_MANAGER_ROLE, &manager).unwrap();
+        assert!(only_role(&storage, LB_HOOKS_MANAGER_ROLE, &manager).is_ok());
+
+        renounce_role(&mut storage, &manager, LB_HOOKS_MANAGER_ROLE).unwrap();
+        assert!(only_role(&storage, LB_HOOKS_MANAGER_ROLE, &manager).is_err());
+
+        grant_role(&mut storage, &owner, LB_HOOKS_MANAGER_ROLE, &manager).unwrap();
+        revoke_role(&mut storage, &owner, LB_HOOKS_MANAGER_ROLE, &manager).unwrap();
+        assert!(!has_role(&storage, LB_HOOKS_MANAGER_ROLE, &manager).unwrap());
+    }
+
+    #[test]
+    fn test_ownership_transfer() {
+        let mut storage = MockStorage::new();
+        let owner_addr = Addr::unchecked("owner");
+        let new_owner = Addr::unchecked("new_owner");
+
+        set_owner(&mut storage, &owner_addr).unwrap();
+
+        assert!(transfer_ownership(&mut storage, &new_owner, &new_owner).is_err());
+        transfer_ownership(&mut storage, &owner_addr, &new_owner).unwrap();
+        assert_eq!(pending_owner(&storage).unwrap(), Some(new_owner.clone()));
+        assert_eq!(owner(&storage).unwrap(), owner_addr);
+
+        assert!(accept_ownership(&mut storage, &owner_addr).is_err());
+        accept_ownership(&mut storage, &new_owner).unwrap();
+        assert_eq!(owner(&storage).unwrap(), new_owner);
+        assert_eq!(pending_owner(&storage).unwrap(), None);
+
+        transfer_ownership(&mut storage, &new_owner, &owner_addr).unwrap();
+        renounce_ownership(&mut storage, &new_owner).unwrap();
+        assert!(only_owner(&storage, &new_owner).is_err());
+        assert!(accept_ownership(&mut storage, &owner_addr).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! The name and version of a contract, stored at instantiation and checked on migration.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Response, StdError, StdResult, Storage};
+use secret_toolkit::storage::Item;
+
+pub static CONTRACT_VERSION: Item<ContractVersion> = Item::new(b"contract_version");
+
+#[cw_serde]
+pub struct ContractVersion {
+    /// The crate name of the contract, e.g. "lb-pair".
+    pub contract: String,
+    /// The semantic version of the contract, e.g. "0.1.0".
+    pub version: String,
+}
+
+pub fn set_contract_version(
+    storage: &mut dyn Storage,
+    contract: &str,
+    version: &str,
+) -> StdResult<()> {
+    CONTRACT_VERSION.save(
+        storage,
+        &ContractVersion {
+            contract: contract.to_string(),
+            version: version.to_string(),
+        },
+    )
+}
+
+pub fn get_contract_version(storage: &dyn Storage) -> StdResult<ContractVersion> {
+    CONTRACT_VERSION.load(storage)
+}
+
+/// Checks that the migration is from the same contract and is not a downgrade, then stores the
+/// new version.
+///
+/// Returns the previous version, or `None` if the contract was instantiated before versions were
+/// stored.
+pub fn migrate_contract_version(
+    storage: &mut dyn Storage,
+    contract: &str,
+    version: &str,
+) -> StdResult<Option<ContractVersion>> {
+    let previous = CONTRACT_VERSION.may_load(storage)?;
+
+    if let Some(previous) = &previous {
+        if previous.contract != contract {
+            return Err(StdError::generic_err(format!(
+                "Cannot migrate from {} to {}",
+                previous.contract, contract
+            )));
+        }
+        if parse_version(&previous.version)? > parse_version(version)? {
+            return Err(StdError::generic_err(format!(
+                "Cannot downgrade {} from {} to {}",
+                contract, previous.version, version
+            )));
+        }
+    }
+
+    set_contract_version(storage, contract, version)?;
+
+    Ok(previous)
+}
+
+/// Returns the response of a migration from `previous`, as returned by `migrate_contract_version`,
+/// to `version`.
+pub fn migrate_response(previous: Option<ContractVersion>, version: &str) -> Response {
+    Response::new()
+        .add_attribute(
+            "previous_version",
+            previous.map_or_else(|| "none".to_string(), |previous| previous.version),
+        )
+        .add_attribute("version", version)
+}
+
+/// A semantic version, as far as its ordering is concerned.
+///
+/// The build metadata is ignored, and a pre-release is lower than the release it precedes.
+#[derive(PartialEq, Eq)]
+struct Version {
+    core: Vec<u64>,
+    pre_release: Option<Vec<PreReleaseIdentifier>>,
+}
+
+/// Numeric identifiers are compared numerically and are lower than alphanumeric ones.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum PreReleaseIdentifier {
+    Numeric(u64),
+    Alphanumeric(String),
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+
+        self.core
+            .cmp(&other.core)
+            .then_with(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+fn parse_version(version: &str) -> StdResult<Version> {
+    let invalid = || StdError::generic_err(format!("Invalid version: {version}"));
+
+    let without_build = version.split('+').next().unwrap_or_default();
+    let (core, pre_release) = match without_build.split_once('-') {
+        Some((core, pre_release)) => (core, Some(pre_release)),
+        None => (without_build, None),
+    };
+
+    let core = core
+        .split('.')
+        .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+        .collect::<StdResult<Vec<u64>>>()?;
+
+    let pre_release = pre_release
+        .map(|pre_release| {
+            pre_release
+                .split('.')
+                .map(|identifier| match identifier.parse::<u64>() {
+                    Ok(number) => Ok(PreReleaseIdentifier::Numeric(number)),
+                    Err(_) if !identifier.is_empty() => {
+                        Ok(PreReleaseIdentifier::Alphanumeric(identifier.to_string()))
+                    }
+                    Err(_) => Err(invalid()),
+                })
+                .collect::<StdResult<Vec<_>>>()
+        })
+        .transpose()?;
+
+    Ok(Version { core, pre_release })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+
+    #[test]
+    fn test_migrate_contract_version() {
+        let mut storage = MockStorage::new();
+
+        assert_eq!(
+            migrate_contract_version(&mut storage, "lb-pair", "0.1.0").unwrap(),
+            None
+        );
+
+        let previous = migrate_contract_version(&mut storage, "lb-pair", "0.2.0")
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous.version, "0.1.0");
+        assert_eq!(get_contract_version(&storage).unwrap().version, "0.2.0");
+
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.1.9").is_err());
+        assert!(migrate_contract_version(&mut storage, "lb-factory", "0.2.0").is_err());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.10.0").is_ok());
+    }
+
+    #[test]
+    fn test_migrate_pre_release_versions() {
+        let mut storage = MockStorage::new();
+
+        migrate_contract_version(&mut storage, "lb-pair", "0.2.0-rc.1").unwrap();
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0-rc.2+build.5").is_ok());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0-rc.1").is_err());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0-rc.10").is_ok());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0").is_ok());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0-rc.11").is_err());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.0+build.6").is_ok());
+        assert!(migrate_contract_version(&mut storage, "lb-pair", "0.2.x").is_err());
+    }
+
+    #[test]
+    fn test_migrate_response() {
+        let response = migrate_response(None, "0.1.0");
+        assert_eq!(response.attributes[0].value, "none");
+        assert_eq!(response.attributes[1].value, "0.1.0");
+
+        let previous = ContractVersion {
+            contract: "lb-pair".to_string(),
+            version: "0.1.0".to_string(),
+        };
+        let response = migrate_response(Some(previous), "0.2.0");
+        assert_eq!(response.attributes[0].value, "0.1.0");
+        assert_eq!(response.attributes[1].value, "0.2.0");
+    }
+}
@@ -1,5 +1,7 @@
+pub mod access_control;
 pub mod assets;
 pub mod callback;
+pub mod contract_version;
 pub mod padding;
 pub mod token_amount;
 pub mod token_type;
@@ -1,7 +1,8 @@
-use super::lb_pair::LbPair;
+use super::lb_pair::{ContractStatus, LbPair};
 use crate::core::{
+    access_control::AccessControlError,
     callback::{ExecuteCallback, InstantiateCallback, Query},
-    RawContract, TokenType,
+    TokenType,
 };
 use crate::libraries::{
     bin_helper::BinError,
@@ -104,12 +105,21 @@ pub enum LbFactoryError {
     #[error("Invalid hooks parameters!")]
     InvalidHooksParameters,
 
-    #[error("Cannot grant default admin role!")]
-    CannotGrantDefaultAdminRole,
-
     // not in joe-v2
     #[error("{0}!")]
     Generic(String),
+    #[error("Hooks code ID {code_id} is already whitelisted!")]
+    HooksAlreadyWhitelisted { code_id: u64 },
+    #[error("Hooks code ID {code_id} is not whitelisted!")]
+    HooksNotWhitelisted { code_id: u64 },
+    #[error("Hooks code hash {code_hash} does not match code ID {code_id}!")]
+    HooksCodeHashMismatch { code_id: u64, code_hash: String },
+    #[error("Timelock delay is too big! Delay: {delay}, max delay: {max_delay}")]
+    TimelockDelayAboveMax { delay: u64, max_delay: u64 },
+    #[error("Timelocked operation {id} does not exist!")]
+    TimelockedOperationNotFound { id: u64 },
+    #[error("Timelocked operation {id} is not ready before {eta}!")]
+    TimelockedOperationNotReady { id: u64, eta: u64 },
     #[error("Only the Owner can do that!")]
     OnlyOwner,
     #[error("Transaction is blocked by contract status")]
@@ -125,6 +135,8 @@ pub enum LbFactoryError {
 
     // from liquidity-book
     #[error(transparent)]
+    AccessControlError(#[from] AccessControlError),
+    #[error(transparent)]
     BinError(#[from] BinError),
     #[error(transparent)]
     FeeError(#[from] FeeError),
@@ -201,6 +213,53 @@ pub struct StaticFeeParameters {
     pub max_volatility_accumulator: u32,
 }
 
+/// A change that waits for the timelock delay before it can be executed.
+#[cw_serde]
+pub enum TimelockedOperation {
+    SetLbPairImplementation {
+        implementation: Implementation,
+    },
+    SetFeeParametersOnPair {
+        token_x: TokenType,
+        token_y: TokenType,
+        bin_step: u16,
+        fee_parameters: StaticFeeParameters,
+    },
+    SetLBHooksParametersOnPair {
+        token_x: TokenType,
+        token_y: TokenType,
+        bin_step: u16,
+        hooks_parameters: HooksParameters,
+        on_hooks_set_data: Binary,
+    },
+    RemoveLBHooksOnPair {
+        token_x: TokenType,
+        token_y: TokenType,
+        bin_step: u16,
+    },
+    /// The pairs are migrated to `implementation`, the LbPair implementation when the operation
+    /// was queued.
+    MigrateLbPairs {
+        implementation: Implementation,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    SetFlashLoanFee {
+        flash_loan_fee: Uint128,
+    },
+    SetTimelockDelay {
+        delay: u64,
+    },
+}
+
+#[cw_serde]
+pub struct PendingOperation {
+    pub id: u64,
+    pub operation: TimelockedOperation,
+    /// The timestamp, in seconds, from which the operation can be executed.
+    pub eta: u64,
+}
+
 pub trait LbFactoryEventExt {
     fn lb_pair_created(
         token_x: String,
@@ -307,21 +366,54 @@ pub trait LbFactoryEventExt {
     fn quote_asset_removed(quote_asset: String) -> Event {
         Event::new("quote_asset_removed").add_attribute_plaintext("quote_asset", quote_asset)
     }
+
+    fn hooks_whitelisted(code_id: u64, code_hash: String) -> Event {
+        Event::new("hooks_whitelisted")
+            .add_attribute_plaintext("code_id", code_id.to_string())
+            .add_attribute_plaintext("code_hash", code_hash)
+    }
+
+    fn hooks_removed_from_whitelist(code_id: u64) -> Event {
+        Event::new("hooks_removed_from_whitelist")
+            .add_attribute_plaintext("code_id", code_id.to_string())
+    }
+
+    fn timelock_delay_set(old_delay: u64, new_delay: u64) -> Event {
+        Event::new("timelock_delay_set")
+            .add_attribute_plaintext("old_delay", old_delay.to_string())
+            .add_attribute_plaintext("new_delay", new_delay.to_string())
+    }
+
+    fn timelocked_operation_queued(id: u64, eta: u64) -> Event {
+        Event::new("timelocked_operation_queued")
+            .add_attribute_plaintext("id", id.to_string())
+            .add_attribute_plaintext("eta", eta.to_string())
+    }
+
+    fn timelocked_operation_executed(id: u64) -> Event {
+        Event::new("timelocked_operation_executed").add_attribute_plaintext("id", id.to_string())
+    }
+
+    fn timelocked_operation_cancelled(id: u64) -> Event {
+        Event::new("timelocked_operation_cancelled").add_attribute_plaintext("id", id.to_string())
+    }
 }
 
 impl LbFactoryEventExt for Event {}
 
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub admin_auth: RawContract,
-    pub query_auth: RawContract,
     pub owner: Option<Addr>,
     pub fee_recipient: Addr,
 }
+
 impl InstantiateCallback for InstantiateMsg {
     const BLOCK_SIZE: usize = 256;
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     SetLbPairImplementation {
@@ -388,6 +480,16 @@ pub enum ExecuteMsg {
         token_y: TokenType,
         bin_step: u16,
     },
+    /// Approves a hooks code, so that pairs can use hooks contracts instantiated from it. Needs
+    /// to be called by the owner.
+    AddHooksToWhitelist {
+        hooks_implementation: Implementation,
+    },
+    /// Removes a hooks code from the whitelist. Pairs already using it are not affected. Needs to
+    /// be called by the owner.
+    RemoveHooksFromWhitelist {
+        code_id: u64,
+    },
     SetFeeRecipient {
         fee_recipient: Addr,
     },
@@ -403,6 +505,65 @@ pub enum ExecuteMsg {
     ForceDecay {
         pair: LbPair,
     },
+    SetPausedOperationsOnPair {
+        token_x: TokenType,
+        token_y: TokenType,
+        bin_step: u16,
+        operations: u8,
+        paused: bool,
+    },
+    /// Sets the contract status of the pairs created by the factory, by their index in the list
+    /// of all pairs. The status is also recorded for the pairs created afterwards. Needs to be
+    /// called by the owner.
+    SetStatusOnAllPairs {
+        status: ContractStatus,
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// Migrates the pairs created by the factory to the current LbPair implementation, by their
+    /// index in the list of all pairs. If the migration is timelocked, the implementation is the
+    /// one set when it was queued.
+    MigrateLbPairs {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    /// Grants a role to an account. Needs to be called by the owner.
+    GrantRole {
+        role: String,
+        account: String,
+    },
+    /// Revokes a role from an account. Needs to be called by the owner.
+    RevokeRole {
+        role: String,
+        account: String,
+    },
+    /// Revokes a role from the caller.
+    RenounceRole {
+        role: String,
+    },
+    /// Sets the delay, in seconds, before the timelocked operations can be executed. A delay of
+    /// zero disables the timelock. Decreasing the delay is itself timelocked. Needs to be called
+    /// by the owner.
+    SetTimelockDelay {
+        delay: u64,
+    },
+    /// Executes a timelocked operation once its delay has passed. Can be called by anyone.
+    ExecuteTimelockedOperation {
+        id: u64,
+    },
+    /// Cancels a timelocked operation. Needs to be called by the owner.
+    CancelTimelockedOperation {
+        id: u64,
+    },
+    /// Starts the ownership transfer to a new owner, who needs to accept it. Needs to be called
+    /// by the owner.
+    TransferOwnership {
+        new_owner: String,
+    },
+    /// Completes the ownership transfer. Needs to be called by the pending owner.
+    AcceptOwnership {},
+    /// Leaves the factory without owner. Needs to be called by the owner.
+    RenounceOwnership {},
 }
 
 impl ExecuteCallback for ExecuteMsg {
@@ -451,12 +612,81 @@ pub enum QueryMsg {
         token_x: TokenType,
         token_y: TokenType,
     },
+    /// Returns the hooks codes that pairs are allowed to use.
+    #[returns(HooksWhitelistResponse)]
+    GetHooksWhitelist {},
+    /// Returns the status recorded for all pairs, and the current status of each pair, by their
+    /// index in the list of all pairs. The status of a pair that fails to answer is unknown.
+    #[returns(PairsStatusResponse)]
+    GetPairsStatus {
+        start_after: Option<u32>,
+        limit: Option<u32>,
+    },
+    #[returns(TimelockDelayResponse)]
+    GetTimelockDelay {},
+    /// Returns the timelocked operations waiting to be executed, by increasing id.
+    #[returns(PendingOperationsResponse)]
+    GetPendingOperations {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(OwnerResponse)]
+    GetOwner {},
+    #[returns(PendingOwnerResponse)]
+    GetPendingOwner {},
+    /// Returns true if the account has the role. The owner has every role.
+    #[returns(HasRoleResponse)]
+    HasRole { role: String, account: String },
 }
 
 impl Query for QueryMsg {
     const BLOCK_SIZE: usize = 256;
 }
 
+#[cw_serde]
+pub struct HooksWhitelistResponse {
+    pub hooks_whitelist: Vec<Implementation>,
+}
+
+#[cw_serde]
+pub struct LbPairStatus {
+    pub lb_pair: LbPair,
+    /// The status of the pair, or `None` if the pair could not be queried.
+    pub contract_status: Option<ContractStatus>,
+}
+
+#[cw_serde]
+pub struct PairsStatusResponse {
+    /// The status set on all pairs, that new pairs inherit.
+    pub status: ContractStatus,
+    pub lb_pairs: Vec<LbPairStatus>,
+}
+
+#[cw_serde]
+pub struct TimelockDelayResponse {
+    pub delay: u64,
+}
+
+#[cw_serde]
+pub struct PendingOperationsResponse {
+    pub pending_operations: Vec<PendingOperation>,
+}
+
+#[cw_serde]
+pub struct OwnerResponse {
+    pub owner: Addr,
+}
+
+#[cw_serde]
+pub struct PendingOwnerResponse {
+    pub pending_owner: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+
 #[cw_serde]
 pub struct MinBinStepResponse {
     pub min_bin_step: u8,
@@ -588,6 +818,16 @@ impl ILbFactory {
         }))
     }
 
+    pub fn get_owner(&self, querier: QuerierWrapper) -> StdResult<Addr> {
+        querier
+            .query_wasm_smart::<OwnerResponse>(
+                self.0.code_hash.clone(),
+                self.0.address.clone(),
+                &QueryMsg::GetOwner {},
+            )
+            .map(|response| response.owner)
+    }
+
     pub fn get_fee_recipient(&self, querier: QuerierWrapper) -> StdResult<Addr> {
         querier
             .query_wasm_smart::<FeeRecipientResponse>(
@@ -1,15 +1,15 @@
 //! # Liquidity Book Flash Loan Callback Interface
 //! Required interface to interact with LB flash loans
 
-use crate::Bytes32;
+use crate::{core::TokenType, Bytes32};
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Binary, ContractInfo};
+use cosmwasm_std::Binary;
 
 pub trait ILbFlashLoanCallback {
     fn lb_flash_loan_callback(
         address: String,
-        token_x: ContractInfo, // snip20
-        token_y: ContractInfo, // snip20
+        token_x: TokenType, // snip20 or native
+        token_y: TokenType, // snip20 or native
         amounts: Bytes32,
         total_fees: Bytes32,
         data: Option<Binary>,
@@ -20,8 +20,8 @@ pub trait ILbFlashLoanCallback {
 pub enum ExecuteMsg {
     LbFlashLoanCallback {
         address: String,
-        token_x: ContractInfo, // snip20
-        token_y: ContractInfo, // snip20
+        token_x: TokenType, // snip20 or native
+        token_y: TokenType, // snip20 or native
         amounts: Bytes32,
         total_fees: Bytes32,
         data: Option<Binary>,
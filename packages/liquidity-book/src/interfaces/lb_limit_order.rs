@@ -0,0 +1,199 @@
+use crate::core::{RawContract, TokenType};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, ContractInfo, Uint128, Uint256};
+use secret_toolkit::permit::Permit;
+
+#[derive(thiserror::Error, Debug)]
+pub enum LbLimitOrderError {
+    #[error("Order {order_id} not found!")]
+    OrderNotFound { order_id: u64 },
+    #[error("Only the owner can cancel order {order_id}!")]
+    OnlyOrderOwner { order_id: u64 },
+    #[error("Order {order_id} is not filled yet!")]
+    OrderNotFilled { order_id: u64 },
+    #[error("Invalid bin {id} for a {order_type} order, the active id is {active_id}!")]
+    InvalidOrderBin {
+        order_type: OrderType,
+        id: u32,
+        active_id: u32,
+    },
+    #[error("Token {token} is not in the pair!")]
+    WrongToken { token: String },
+    #[error("Invalid tip {tip} for an order of {amount}!")]
+    InvalidTip { tip: Uint128, amount: Uint128 },
+    #[error("Exactly one native coin must be sent to place an order!")]
+    InvalidFunds,
+
+    // --- not in joe-v2 ---
+    //
+    #[error("Unknown reply id: {id}")]
+    UnknownReplyId { id: u64 },
+    #[error("Reply data is missing!")]
+    ReplyDataMissing,
+    #[error("Wrong viewing key for this address or viewing key not set")]
+    Unauthorized,
+    #[error("Owner permit required, got permissions {0}")]
+    OwnerPermitRequired(String),
+
+    #[error(transparent)]
+    StdError(#[from] cosmwasm_std::StdError),
+}
+
+/// The side of a limit order.
+/// - Bid: deposits token Y below the active id, to buy token X
+/// - Ask: deposits token X above the active id, to sell it for token Y
+#[cw_serde]
+#[derive(Copy)]
+pub enum OrderType {
+    Bid,
+    Ask,
+}
+
+impl std::fmt::Display for OrderType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderType::Bid => write!(f, "bid"),
+            OrderType::Ask => write!(f, "ask"),
+        }
+    }
+}
+
+/// A limit order, backed by single-sided liquidity in one bin of an LB pair.
+#[cw_serde]
+pub struct Order {
+    pub order_id: u64,
+    pub owner: Addr,
+    pub lb_pair: ContractInfo,
+    pub order_type: OrderType,
+    pub id: u32,
+    pub token_in: TokenType,
+    pub amount_in: Uint128,
+    pub shares: Uint256,
+    /// Paid in `token_in` to whoever executes the order once it is filled.
+    pub tip: Uint128,
+}
+
+/// The parameters of a new limit order. The token sent with the order decides its type.
+#[cw_serde]
+pub struct PlaceOrderParams {
+    pub token_x: TokenType,
+    pub token_y: TokenType,
+    pub bin_step: u16,
+    pub id: u32,
+    pub tip: Uint128,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub factory: RawContract,
+    /// Seeds the viewing keys created by the contract.
+    pub entropy: String,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Places an order with the native token sent along the message.
+    PlaceOrder {
+        params: PlaceOrderParams,
+    },
+    /// Burns the liquidity of filled orders to their owners, and pays their tips to the sender.
+    ExecuteOrders {
+        order_ids: Vec<u64>,
+    },
+    /// Burns the liquidity of an order to its owner, and refunds the tip.
+    CancelOrder {
+        order_id: u64,
+    },
+    Register {
+        address: String,
+        code_hash: String,
+    },
+    Receive {
+        sender: Addr,
+        from: Addr,
+        amount: Uint128,
+        memo: Option<String>,
+        msg: Binary,
+    },
+    CreateViewingKey {
+        entropy: String,
+    },
+    SetViewingKey {
+        key: String,
+    },
+    RevokePermit {
+        permit_name: String,
+    },
+}
+
+/// The messages accepted in the `msg` of a SNIP-20 `Send` to this contract.
+#[cw_serde]
+pub enum InvokeMsg {
+    PlaceOrder { params: PlaceOrderParams },
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(FactoryResponse)]
+    GetFactory {},
+    /// Returns an order of `account`. The key is the viewing key of `account`.
+    #[returns(OrderResponse)]
+    GetOrder {
+        order_id: u64,
+        account: String,
+        key: String,
+    },
+    /// Returns a page of the open orders of `account`. The key is the viewing key of `account`.
+    #[returns(UserOrdersResponse)]
+    GetUserOrders {
+        account: String,
+        key: String,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+    #[returns(Binary)]
+    WithPermit {
+        permit: Permit,
+        query: QueryWithPermit,
+    },
+}
+
+/// Order queries authenticated with a SNIP-24 query permit.
+///
+/// The account is the signer of the permit, which must have the `Owner` permission.
+#[cw_serde]
+pub enum QueryWithPermit {
+    GetOrder {
+        order_id: u64,
+    },
+    GetUserOrders {
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct CreateViewingKeyResponse {
+    pub key: String,
+}
+
+#[cw_serde]
+pub struct FactoryResponse {
+    pub factory: ContractInfo,
+}
+
+#[cw_serde]
+pub struct OrderResponse {
+    pub order: Order,
+    pub is_filled: bool,
+}
+
+#[cw_serde]
+pub struct UserOrdersResponse {
+    pub orders: Vec<OrderResponse>,
+    pub total: u32,
+}
@@ -1,15 +1,16 @@
 use super::lb_factory::{Implementation, StaticFeeParameters};
+use super::lb_token::expiration::Expiration;
 use crate::core::{
     callback::{ExecuteCallback, InstantiateCallback, Query},
-    RawContract, TokenType,
+    TokenType,
 };
 use crate::libraries::{hooks::HooksParameters, Bytes32, LiquidityConfigurations};
-use base64::prelude::{Engine as _, BASE64_STANDARD};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{
     to_binary, Addr, Binary, ContractInfo, Event, QuerierWrapper, StdResult, Uint128, Uint256,
     WasmMsg,
 };
+use secret_toolkit::permit::Permit;
 use std::fmt::{Debug, Display};
 use std::ops::Deref;
 
@@ -73,6 +74,8 @@ pub enum LbPairError {
     MaxTotalFeeExceeded,
     #[error("Invalid hooks!")]
     InvalidHooks,
+    #[error("Reentrant call!")]
+    ReentrantCall,
 
     // --- not in joe-v2 ---
     //
@@ -88,6 +91,41 @@ pub enum LbPairError {
     UnknownReplyId { id: u64 },
     #[error("Reply data is missing!")]
     ReplyDataMissing,
+    #[error("Native token {denom} is not part of this pair!")]
+    UnsupportedNativeToken { denom: String },
+    #[error("Operation is paused!")]
+    OperationPaused,
+    #[error("Id limit {id_limit} is past the active id {active_id}!")]
+    InvalidIdLimit { id_limit: u32, active_id: u32 },
+    #[error(
+        "Invalid TWAP window: {seconds_ago_start} seconds ago to {seconds_ago_end} seconds ago!"
+    )]
+    InvalidTwapWindow {
+        seconds_ago_start: u64,
+        seconds_ago_end: u64,
+    },
+    #[error("Oracle is not initialized!")]
+    OracleNotInitialized,
+
+    #[error("Cannot simulate a swap at {at_timestamp}, before the last update at {time_of_last_update}!")]
+    InvalidSimulationTimestamp {
+        at_timestamp: u64,
+        time_of_last_update: u64,
+    },
+    #[error("Only the Factory or its Owner can do that!")]
+    OnlyFactoryOrOwner,
+    #[error("Token {token} is not part of this pair!")]
+    NoMatchingTokenInPair { token: String },
+    #[error("No pending mint for {account}!")]
+    NoPendingMint { account: String },
+    #[error("Too many lookup timestamps: {count}, the maximum is {max}!")]
+    TooManyLookupTimestamps { count: usize, max: usize },
+    #[error("Too many bin ids: {count}, the maximum is {max}!")]
+    TooManyIds { count: usize, max: usize },
+    #[error("The pending mint of {account} has different parameters!")]
+    PendingMintMismatch { account: String },
+    #[error("Flash loan exceeds the reserves!")]
+    FlashLoanExceedsReserves,
 
     // Error Wrappings from Dependencies
     #[error(transparent)]
@@ -140,34 +178,41 @@ pub struct Snip20ReceiveMsg {
     pub msg: Option<Binary>,
 }
 
-// TODO: Decide which attributes to make private.
-// NOTE: All Bytes32 values are represented as Base64 strings. Should we use hex instead?
+/// Decodes packed amounts into `[amount_x, amount_y]`, so events carry readable decimal values.
+fn decode_amounts(amounts: &Bytes32) -> [Uint128; 2] {
+    let (amount_x, amount_y) = amounts.decode();
+
+    [Uint128::new(amount_x), Uint128::new(amount_y)]
+}
+
+fn amounts_to_json(amounts: &Bytes32) -> String {
+    serde_json_wasm::to_string(&decode_amounts(amounts)).unwrap()
+}
+
+fn batch_amounts_to_json(amounts: &[Bytes32]) -> String {
+    let amounts: Vec<[Uint128; 2]> = amounts.iter().map(decode_amounts).collect();
+
+    serde_json_wasm::to_string(&amounts).unwrap()
+}
+
+// NOTE: All Bytes32 values are decoded and represented as `["amount_x","amount_y"]` JSON arrays.
+// Per-bin values are arrays of those, in the same order as `ids`.
 // TODO: We are doing a lot of unwrapping here. Is that ok?
 pub trait LbPairEventExt {
     fn deposited_to_bins(sender: &Addr, to: &Addr, ids: &[u32], amounts: &[Bytes32]) -> Event {
-        let amounts: Vec<String> = amounts
-            .iter()
-            .map(|amount| BASE64_STANDARD.encode(amount))
-            .collect();
-
         Event::new("deposited_to_bins")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("to", to)
+            .add_attribute("sender", sender)
+            .add_attribute("to", to)
             .add_attribute_plaintext("ids", serde_json_wasm::to_string(&ids).unwrap())
-            .add_attribute_plaintext("amounts", serde_json_wasm::to_string(&amounts).unwrap())
+            .add_attribute("amounts", batch_amounts_to_json(amounts))
     }
 
     fn withdrawn_from_bins(sender: &Addr, to: &Addr, ids: &[u32], amounts: &[Bytes32]) -> Event {
-        let amounts: Vec<String> = amounts
-            .iter()
-            .map(|amount| BASE64_STANDARD.encode(amount))
-            .collect();
-
         Event::new("withdrawn_from_bins")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("to", to)
+            .add_attribute("sender", sender)
+            .add_attribute("to", to)
             .add_attribute_plaintext("ids", serde_json_wasm::to_string(&ids).unwrap())
-            .add_attribute_plaintext("amounts", serde_json_wasm::to_string(&amounts).unwrap())
+            .add_attribute("amounts", batch_amounts_to_json(amounts))
     }
 
     fn composition_fees(
@@ -177,16 +222,16 @@ pub trait LbPairEventExt {
         protocol_fees: &Bytes32,
     ) -> Event {
         Event::new("composition_fees")
-            .add_attribute_plaintext("sender", sender)
+            .add_attribute("sender", sender)
             .add_attribute_plaintext("id", id.to_string())
-            .add_attribute_plaintext("total_fees", BASE64_STANDARD.encode(total_fees))
-            .add_attribute_plaintext("protocol_fees", BASE64_STANDARD.encode(protocol_fees))
+            .add_attribute_plaintext("total_fees", amounts_to_json(total_fees))
+            .add_attribute_plaintext("protocol_fees", amounts_to_json(protocol_fees))
     }
 
     fn collected_protocol_fees(fee_recipient: &Addr, protocol_fees: &Bytes32) -> Event {
         Event::new("collected_protocol_fees")
             .add_attribute_plaintext("fee_recipient", fee_recipient)
-            .add_attribute_plaintext("protocol_fees", BASE64_STANDARD.encode(protocol_fees))
+            .add_attribute_plaintext("protocol_fees", amounts_to_json(protocol_fees))
     }
 
     fn swap(
@@ -200,14 +245,14 @@ pub trait LbPairEventExt {
         protocol_fees: Bytes32,
     ) -> Event {
         Event::new("swap")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("to", to)
+            .add_attribute("sender", sender)
+            .add_attribute("to", to)
             .add_attribute_plaintext("id", id.to_string())
-            .add_attribute_plaintext("amounts_in", BASE64_STANDARD.encode(amounts_in))
-            .add_attribute_plaintext("amounts_out", BASE64_STANDARD.encode(amounts_out))
+            .add_attribute_plaintext("amounts_in", amounts_to_json(&amounts_in))
+            .add_attribute_plaintext("amounts_out", amounts_to_json(&amounts_out))
             .add_attribute_plaintext("volatility_accumulator", volatility_accumulator.to_string())
-            .add_attribute_plaintext("total_fees", BASE64_STANDARD.encode(total_fees))
-            .add_attribute_plaintext("protocol_fees", BASE64_STANDARD.encode(protocol_fees))
+            .add_attribute_plaintext("total_fees", amounts_to_json(&total_fees))
+            .add_attribute_plaintext("protocol_fees", amounts_to_json(&protocol_fees))
     }
 
     fn static_fee_parameters_set(
@@ -252,12 +297,12 @@ pub trait LbPairEventExt {
         protocol_fees: &Bytes32,
     ) -> Event {
         Event::new("flash_loan")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("receiver", receiver)
+            .add_attribute("sender", sender)
+            .add_attribute("receiver", receiver)
             .add_attribute_plaintext("active_id", active_id.to_string())
-            .add_attribute_plaintext("amounts", BASE64_STANDARD.encode(amounts))
-            .add_attribute_plaintext("total_fees", BASE64_STANDARD.encode(total_fees))
-            .add_attribute_plaintext("protocol_fees", BASE64_STANDARD.encode(protocol_fees))
+            .add_attribute_plaintext("amounts", amounts_to_json(amounts))
+            .add_attribute_plaintext("total_fees", amounts_to_json(total_fees))
+            .add_attribute_plaintext("protocol_fees", amounts_to_json(protocol_fees))
     }
 
     fn oracle_length_increased(sender: &Addr, oracle_length: u16) -> Event {
@@ -287,11 +332,11 @@ pub struct InstantiateMsg {
     pub lb_token_implementation: Implementation,
     pub viewing_key: String,
     pub entropy: String,
-    // TODO: Decide about getting rid of these.
-    pub admin_auth: RawContract,
-    pub query_auth: RawContract,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 // impl InstantiateCallback for InstantiateMsg {
 //     const BLOCK_SIZE: usize = 256;
 // }
@@ -301,6 +346,7 @@ pub enum ExecuteMsg {
     Swap {
         swap_for_y: bool,
         to: String,
+        id_limit: Option<u32>,
     },
     // TODO: figure out proper types here
     FlashLoan {
@@ -349,6 +395,14 @@ pub enum ExecuteMsg {
         spender: String,
         approved: bool,
     },
+    /// Sets the amount of shares of the bin `id` that `spender` can transfer on behalf of the
+    /// caller, replacing any previous allowance for that bin.
+    Approve {
+        spender: String,
+        id: u32,
+        amount: Uint256,
+        expiration: Option<Expiration>,
+    },
     // BatchTransferFrom {
     //     from: String,
     //     to: String,
@@ -360,7 +414,22 @@ pub enum ExecuteMsg {
     SetContractStatus {
         contract_status: ContractStatus,
     },
-    // Receive(Snip20ReceiveMsg),
+    CreateViewingKey {
+        entropy: String,
+    },
+    SetViewingKey {
+        key: String,
+    },
+    RevokePermit {
+        permit_name: String,
+    },
+    SetPausedOperations {
+        operations: u8,
+        paused: bool,
+    },
+    /// Refunds the tokens sent for a mint that is still waiting for its other token.
+    WithdrawPendingMint {},
+    Receive(Snip20ReceiveMsg),
 }
 
 // TODO: should this be here at all?
@@ -377,6 +446,14 @@ impl Display for ContractStatus {
     }
 }
 
+// Pausable operations, as positions in the paused operations bitmap
+pub const PAUSE_SWAP: u8 = 1 << 0;
+pub const PAUSE_MINT: u8 = 1 << 1;
+pub const PAUSE_BURN: u8 = 1 << 2;
+pub const PAUSE_FLASH_LOAN: u8 = 1 << 3;
+pub const PAUSE_TRANSFER: u8 = 1 << 4;
+pub const PAUSE_COLLECT_PROTOCOL_FEES: u8 = 1 << 5;
+
 impl ExecuteCallback for ExecuteMsg {
     const BLOCK_SIZE: usize = 256;
 }
@@ -384,7 +461,18 @@ impl ExecuteCallback for ExecuteMsg {
 #[cw_serde]
 pub enum InvokeMsg {
     // TODO: do we need a separate InvokeMsg for swaps?
-    Swap { swap_for_y: bool, to: String },
+    Swap {
+        swap_for_y: bool,
+        to: String,
+        id_limit: Option<u32>,
+    },
+    /// Mints with the tokens sent by the same account. When the configurations need both
+    /// tokens, the first deposit is kept as a pending mint until the other token arrives.
+    Mint {
+        to: String,
+        liquidity_configs: Vec<LiquidityConfigurations>,
+        refund_to: String,
+    },
 }
 
 // impl ExecuteCallback for InvokeMsg {
@@ -409,6 +497,11 @@ pub struct BurnResponse {
     pub amounts: Vec<Bytes32>,
 }
 
+#[cw_serde]
+pub struct CreateViewingKeyResponse {
+    pub key: String,
+}
+
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
@@ -448,11 +541,21 @@ pub enum QueryMsg {
     GetSwapIn {
         amount_out: Uint128,
         swap_for_y: bool,
+        id_limit: Option<u32>,
+        /// Simulates the swap as if it happened at this time, instead of the current block time.
+        at_timestamp: Option<u64>,
+        /// Starts the swap from this volatility, instead of the one derived from the current state.
+        volatility_accumulator: Option<u32>,
     },
     #[returns(SwapOutResponse)]
     GetSwapOut {
         amount_in: Uint128,
         swap_for_y: bool,
+        id_limit: Option<u32>,
+        /// Simulates the swap as if it happened at this time, instead of the current block time.
+        at_timestamp: Option<u64>,
+        /// Starts the swap from this volatility, instead of the one derived from the current state.
+        volatility_accumulator: Option<u32>,
     },
 
     // lb-token
@@ -463,14 +566,23 @@ pub enum QueryMsg {
     #[returns(Uint256)]
     TotalSupply { id: u32 },
     #[returns(Uint256)]
-    BalanceOf { account: String, id: u32 },
+    BalanceOf {
+        account: String,
+        key: String,
+        id: u32,
+    },
     #[returns(Vec<Uint256>)]
     BalanceOfBatch {
         accounts: Vec<String>,
+        key: String,
         ids: Vec<u32>,
     },
     #[returns(bool)]
-    IsApprovedForAll { owner: String, spender: String },
+    IsApprovedForAll {
+        owner: String,
+        spender: String,
+        key: String,
+    },
 
     // not in joe-v2
     #[returns(LbTokenResponse)]
@@ -485,12 +597,111 @@ pub enum QueryMsg {
         page: Option<u32>,
         page_size: Option<u32>,
     },
+    #[returns(BinsInRangeResponse)]
+    GetBinsInRange {
+        start_id: Option<u32>,
+        end_id: Option<u32>,
+        direction: Direction,
+        limit: Option<u32>,
+    },
+    #[returns(LiquidityDepthResponse)]
+    GetLiquidityDepth { radius: u32 },
+    #[returns(PausedOperationsResponse)]
+    GetPausedOperations {},
+    #[returns(ContractStatusResponse)]
+    GetContractStatus {},
+    /// Returns the oracle samples at up to 100 timestamps.
+    #[returns(OracleSamplesAtResponse)]
+    GetOracleSamplesAt { lookup_timestamps: Vec<u64> },
+    #[returns(TwapResponse)]
+    GetTwap {
+        seconds_ago_start: u64,
+        seconds_ago_end: u64,
+    },
+    /// Returns a page of the bins in which `account` has shares.
+    /// The bins of pairs migrated from a version without this query are only tracked once the
+    /// account's balance in them changes.
+    #[returns(UserBinsResponse)]
+    GetUserBins {
+        account: String,
+        key: String,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+    /// Returns the fees earned by `account` in each of the bins `ids` since its first deposit.
+    /// If `ids` is not provided, a page of the bins in which the account currently has shares is
+    /// used, in the same order as `GetUserBins`. At most 100 ids can be provided.
+    /// On pairs migrated from a version without this query, the fees of a bin are counted from
+    /// the first change of the account's balance in it after the migration.
+    #[returns(AccruedFeesResponse)]
+    GetAccruedFees {
+        account: String,
+        key: String,
+        ids: Option<Vec<u32>>,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+    /// Returns the shares of the bin `id` that `spender` can transfer on behalf of `owner`.
+    /// The key can be the viewing key of either the owner or the spender.
+    #[returns(AllowanceResponse)]
+    GetAllowance {
+        owner: String,
+        spender: String,
+        id: u32,
+        key: String,
+    },
+    #[returns(Binary)]
+    WithPermit {
+        permit: Permit,
+        query: QueryWithPermit,
+    },
 }
 
 impl Query for QueryMsg {
     const BLOCK_SIZE: usize = 256;
 }
 
+/// LB token queries authenticated with a SNIP-24 query permit.
+///
+/// The account is the signer of the permit, which must have the `Owner` permission.
+#[cw_serde]
+pub enum QueryWithPermit {
+    BalanceOf {
+        id: u32,
+    },
+    BalanceOfBatch {
+        ids: Vec<u32>,
+    },
+    IsApprovedForAll {
+        owner: String,
+        spender: String,
+    },
+    GetUserBins {
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+    GetAccruedFees {
+        ids: Option<Vec<u32>>,
+        page: Option<u32>,
+        page_size: Option<u32>,
+    },
+    GetAllowance {
+        owner: String,
+        spender: String,
+        id: u32,
+    },
+}
+
+/// The order in which bins are enumerated.
+#[cw_serde]
+#[derive(Copy)]
+pub enum Direction {
+    /// From lower to higher ids (higher prices of token X).
+    Ascending,
+    /// From higher to lower ids (lower prices of token X).
+    Descending,
+}
+
 // TODO: should all the query response types start with "Get"?
 
 #[cw_serde]
@@ -603,6 +814,11 @@ pub struct OracleSampleAtResponse {
     pub sample: OracleSampleResponse,
 }
 
+#[cw_serde]
+pub struct OracleSamplesAtResponse {
+    pub samples: Vec<OracleSampleResponse>,
+}
+
 #[cw_serde]
 pub struct PriceFromIdResponse {
     pub price: Uint256,
@@ -618,6 +834,7 @@ pub struct SwapInResponse {
     pub amount_in: Uint128,
     pub amount_out_left: Uint128,
     pub fee: Uint128,
+    pub bin_fees: Vec<BinFeeResponse>,
 }
 
 #[cw_serde]
@@ -625,6 +842,16 @@ pub struct SwapOutResponse {
     pub amount_in_left: Uint128,
     pub amount_out: Uint128,
     pub fee: Uint128,
+    pub bin_fees: Vec<BinFeeResponse>,
+}
+
+/// The fees applied in a bin crossed by a simulated swap, with 18 decimals.
+#[cw_serde]
+pub struct BinFeeResponse {
+    pub bin_id: u32,
+    pub base_fee: Uint128,
+    pub variable_fee: Uint128,
+    pub total_fee: Uint128,
 }
 
 #[cw_serde]
@@ -647,6 +874,107 @@ pub struct AllBinsResponse {
     pub current_block_height: u64,
 }
 
+#[cw_serde]
+pub struct BinInRangeResponse {
+    pub bin_id: u32,
+    pub bin_reserve_x: Uint128,
+    pub bin_reserve_y: Uint128,
+    pub total_supply: Uint256,
+    pub price: Uint256,
+}
+
+#[cw_serde]
+pub struct BinsInRangeResponse {
+    pub bins: Vec<BinInRangeResponse>,
+    /// The id to use as `start_id` to continue the enumeration, or `None` if the range is exhausted.
+    pub next_id: Option<u32>,
+}
+
+#[cw_serde]
+pub struct LiquidityDepthBinResponse {
+    pub bin_id: u32,
+    pub price: Uint256,
+    pub bin_reserve_x: Uint128,
+    pub bin_reserve_y: Uint128,
+    /// The amount of token X in the bins between the active bin and this one, both inclusive.
+    /// Above the active bin, the active bin itself is excluded.
+    pub cumulative_x: Uint128,
+    /// The amount of token Y in the bins between the active bin and this one, both inclusive.
+    /// Above the active bin, the active bin itself is excluded.
+    pub cumulative_y: Uint128,
+}
+
+#[cw_serde]
+pub struct LiquidityDepthResponse {
+    pub active_id: u32,
+    pub bins: Vec<LiquidityDepthBinResponse>,
+}
+
+#[cw_serde]
+pub struct TwapResponse {
+    /// The time-weighted average active id over the window.
+    pub average_id: u32,
+    /// The price of the average id, as a 128.128-binary fixed-point number.
+    pub price: Uint256,
+    /// The price of the average id, with 18 decimals.
+    pub price_decimal: Uint256,
+    /// The time-weighted average volatility accumulator over the window.
+    pub average_volatility: u64,
+    /// The number of bins crossed by swaps during the window.
+    pub bins_crossed: u64,
+}
+
+#[cw_serde]
+pub struct PausedOperationsResponse {
+    pub paused_operations: u8,
+    pub swap: bool,
+    pub mint: bool,
+    pub burn: bool,
+    pub flash_loan: bool,
+    pub transfer: bool,
+    pub collect_protocol_fees: bool,
+}
+
+#[cw_serde]
+pub struct ContractStatusResponse {
+    pub contract_status: ContractStatus,
+}
+
+#[cw_serde]
+pub struct UserBinResponse {
+    pub bin_id: u32,
+    pub shares: Uint256,
+    pub amount_x: Uint128,
+    pub amount_y: Uint128,
+}
+
+#[cw_serde]
+pub struct UserBinsResponse {
+    pub bins: Vec<UserBinResponse>,
+    pub total: u32,
+}
+
+#[cw_serde]
+pub struct AccruedFeesBinResponse {
+    pub bin_id: u32,
+    pub fees_x: Uint128,
+    pub fees_y: Uint128,
+}
+
+#[cw_serde]
+pub struct AccruedFeesResponse {
+    pub bins: Vec<AccruedFeesBinResponse>,
+    pub total_fees_x: Uint128,
+    pub total_fees_y: Uint128,
+}
+
+/// An expired allowance is returned with a zero amount.
+#[cw_serde]
+pub struct AllowanceResponse {
+    pub amount: Uint256,
+    pub expiration: Expiration,
+}
+
 /// A thin wrapper around `ContractInfo` that provides additional
 /// methods to interact with an LB Pair contract.
 #[cw_serde]
@@ -704,8 +1032,22 @@ impl ILbPair {
             .map(|response| response.hooks_parameters)
     }
 
-    pub fn swap(&self, swap_for_y: bool, to: String) -> StdResult<WasmMsg> {
-        let msg = ExecuteMsg::Swap { swap_for_y, to };
+    pub fn get_contract_status(&self, querier: QuerierWrapper) -> StdResult<ContractStatus> {
+        querier
+            .query_wasm_smart::<ContractStatusResponse>(
+                self.0.code_hash.clone(),
+                self.0.address.clone(),
+                &QueryMsg::GetContractStatus {},
+            )
+            .map(|response| response.contract_status)
+    }
+
+    pub fn swap(&self, swap_for_y: bool, to: String, id_limit: Option<u32>) -> StdResult<WasmMsg> {
+        let msg = ExecuteMsg::Swap {
+            swap_for_y,
+            to,
+            id_limit,
+        };
 
         Ok(WasmMsg::Execute {
             contract_addr: self.address.to_string(),
@@ -880,4 +1222,24 @@ impl ILbPair {
             funds: vec![],
         })
     }
+    pub fn set_paused_operations(&self, operations: u8, paused: bool) -> StdResult<WasmMsg> {
+        let msg = ExecuteMsg::SetPausedOperations { operations, paused };
+
+        Ok(WasmMsg::Execute {
+            contract_addr: self.address.to_string(),
+            code_hash: self.code_hash.clone(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        })
+    }
+
+    /// Migrates the pair to `implementation`. The caller must be the admin of the pair.
+    pub fn migrate(&self, implementation: &Implementation) -> StdResult<WasmMsg> {
+        Ok(WasmMsg::Migrate {
+            contract_addr: self.address.to_string(),
+            code_hash: implementation.code_hash.clone(),
+            code_id: implementation.id,
+            msg: to_binary(&MigrateMsg {})?,
+        })
+    }
 }
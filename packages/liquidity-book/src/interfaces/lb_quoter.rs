@@ -38,6 +38,9 @@ pub struct InstantiateMsg {
     pub router_v2_2: Option<RawContract>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub struct ExecuteMsg {}
 
@@ -106,6 +106,8 @@ pub enum LbRouterError {
     UnknownReplyId { id: u64 },
     #[error("Reply data is missing!")]
     ReplyDataMissing,
+    #[error("Native token {denom} is not supported!")]
+    NativeTokenNotSupported { denom: String },
 
     #[error(transparent)]
     StdError(#[from] cosmwasm_std::StdError),
@@ -185,6 +187,9 @@ pub struct InstantiateMsg {
     pub factory: ContractInfo,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     CreateLbPair {
@@ -300,6 +305,13 @@ pub enum ExecuteMsg {
     RegisterBatch {
         tokens: Vec<RawContract>,
     },
+    /// Creates a pair through the factory and adds liquidity to it in the same transaction, so
+    /// that nobody can move the active id in between. The pair is created with the tokens and bin
+    /// step of the liquidity parameters.
+    CreateLbPairAndAddLiquidity {
+        active_id: u32,
+        liquidity_parameters: LiquidityParameters,
+    },
     Receive {
         sender: Addr,
         from: Addr,
@@ -314,6 +326,12 @@ pub struct CreateLbPairResponse {
     pub lb_pair: LbPair,
 }
 
+#[cw_serde]
+pub struct CreateLbPairAndAddLiquidityResponse {
+    pub lb_pair: LbPair,
+    pub add_liquidity: AddLiquidityResponse,
+}
+
 #[cw_serde]
 pub struct AddLiquidityResponse {
     pub amount_x_added: Uint128,
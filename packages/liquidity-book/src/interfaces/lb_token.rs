@@ -31,17 +31,17 @@ pub trait LbTokenEventExt {
         amounts: &Vec<Uint256>,
     ) -> Event {
         Event::new("transfer_batch")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("from", from)
-            .add_attribute_plaintext("to", to)
+            .add_attribute("sender", sender)
+            .add_attribute("from", from)
+            .add_attribute("to", to)
             .add_attribute_plaintext("ids", serde_json_wasm::to_string(&ids).unwrap())
-            .add_attribute_plaintext("amounts", serde_json_wasm::to_string(&amounts).unwrap())
+            .add_attribute("amounts", serde_json_wasm::to_string(&amounts).unwrap())
     }
 
     fn approval_for_all(account: &Addr, sender: &Addr, approved: bool) -> Event {
         Event::new("approval_for_all")
-            .add_attribute_plaintext("account", account)
-            .add_attribute_plaintext("sender", sender)
+            .add_attribute("account", account)
+            .add_attribute("sender", sender)
             .add_attribute_plaintext("approved", approved.to_string())
     }
 }
@@ -67,10 +67,14 @@ pub struct InstantiateMsg {
     pub lb_pair_info: LbPair,
     pub initial_tokens: Vec<CurateTokenId>,
 }
+
 impl InstantiateCallback for InstantiateMsg {
     const BLOCK_SIZE: usize = 256;
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 /////////////////////////////////////////////////////////////////////////////////
 // Handle Messages
 /////////////////////////////////////////////////////////////////////////////////
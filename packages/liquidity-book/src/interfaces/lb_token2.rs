@@ -1,3 +1,4 @@
+use super::lb_token::expiration::Expiration;
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Event, Uint256};
 
@@ -11,6 +12,14 @@ pub enum LbTokenError {
     SelfApproval(String),
     #[error("SpenderNotApproved: from {from}, spender {spender}")]
     SpenderNotApproved { from: String, spender: String },
+    #[error("InsufficientAllowance: from {from}, spender {spender}, id {id}, allowance {allowance}, amount {amount}")]
+    InsufficientAllowance {
+        from: String,
+        spender: String,
+        id: u32,
+        allowance: Uint256,
+        amount: Uint256,
+    },
     #[error("TransferExceedsBalance: from {from}, id {id}, amount {amount}")]
     TransferExceedsBalance {
         from: Addr,
@@ -24,6 +33,11 @@ pub enum LbTokenError {
         amount: Uint256,
     },
 
+    #[error("Wrong viewing key for this address or viewing key not set")]
+    Unauthorized,
+    #[error("Owner permit required, got permissions {0}")]
+    OwnerPermitRequired(String),
+
     #[error(transparent)]
     StdError(#[from] cosmwasm_std::StdError),
 }
@@ -37,19 +51,34 @@ pub trait LbTokenEventExt {
         amounts: Vec<Uint256>,
     ) -> Event {
         Event::new("transfer_batch")
-            .add_attribute_plaintext("sender", sender)
-            .add_attribute_plaintext("from", from)
-            .add_attribute_plaintext("to", to)
-            .add_attribute_plaintext("ids", format!("{:?}", ids))
-            .add_attribute_plaintext("amounts", format!("{:?}", amounts))
+            .add_attribute("sender", sender)
+            .add_attribute("from", from)
+            .add_attribute("to", to)
+            .add_attribute_plaintext("ids", serde_json_wasm::to_string(&ids).unwrap())
+            .add_attribute("amounts", serde_json_wasm::to_string(&amounts).unwrap())
     }
 
     fn approval_for_all(account: String, sender: String, approved: bool) -> Event {
         Event::new("approval_for_all")
-            .add_attribute_plaintext("account", account)
-            .add_attribute_plaintext("sender", sender)
+            .add_attribute("account", account)
+            .add_attribute("sender", sender)
             .add_attribute_plaintext("approved", approved.to_string())
     }
+
+    fn approval(
+        account: String,
+        spender: String,
+        id: u32,
+        amount: Uint256,
+        expiration: Expiration,
+    ) -> Event {
+        Event::new("approval")
+            .add_attribute("account", account)
+            .add_attribute("spender", spender)
+            .add_attribute_plaintext("id", id.to_string())
+            .add_attribute("amount", amount.to_string())
+            .add_attribute_plaintext("expiration", expiration.to_string())
+    }
 }
 
 impl LbTokenEventExt for Event {}
@@ -1,6 +1,22 @@
+//! Messages, errors and events of the Liquidity Book contracts.
+//!
+//! # Event privacy
+//!
+//! Events are public unless their attributes are added with `add_attribute`, in which case they
+//! are encrypted for the transaction sender. All the events follow the same rule:
+//!
+//! * Accounts taking part in a deposit, withdrawal, swap, flash loan, share transfer or approval
+//!   (`sender`, `to`, `from`, `receiver`, `account`, `spender`) are encrypted.
+//! * Amounts that describe an account's position (deposited and withdrawn amounts, transferred
+//!   shares, allowances) are encrypted, so that private balances can't be rebuilt from events.
+//! * Bin ids and pool-wide values (swap amounts, fees, volatility, parameters) are public, so
+//!   that indexers can follow the state of the pools.
+//! * Admin events, such as parameter or role changes, are public.
+
 pub mod lb_factory;
 pub mod lb_flash_loan_callback;
 pub mod lb_hooks;
+pub mod lb_limit_order;
 pub mod lb_pair;
 pub mod lb_quoter;
 pub mod lb_router;
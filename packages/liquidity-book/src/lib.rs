@@ -1,5 +1,7 @@
 pub mod core;
 pub mod interfaces;
 pub mod libraries;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use libraries::Bytes32;
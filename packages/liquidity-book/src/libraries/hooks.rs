@@ -1,3 +1,4 @@
+use super::LiquidityConfigurations;
 use crate::{interfaces::lb_hooks::ExecuteMsg, Bytes32};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::Uint256;
@@ -342,6 +343,200 @@ pub fn after_swap(
     }
 }
 
+/// Helper function to call the beforeFlashLoan function on the hooks contract, only if the
+/// BEFORE_FLASH_LOAN_FLAG is set in the hooksParameters
+pub fn before_flash_loan(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    to: &Addr,
+    amounts: Bytes32,
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & BEFORE_FLASH_LOAN != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::BeforeFlashLoan {
+                        sender: sender.to_string(),
+                        to: to.to_string(),
+                        amounts,
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Helper function to call the afterFlashLoan function on the hooks contract, only if the
+/// AFTER_FLASH_LOAN_FLAG is set in the hooksParameters
+pub fn after_flash_loan(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    to: &Addr,
+    fees: Bytes32,
+    fees_received: Bytes32,
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & AFTER_FLASH_LOAN != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::AfterFlashLoan {
+                        sender: sender.to_string(),
+                        to: to.to_string(),
+                        fees,
+                        fees_received,
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Helper function to call the beforeMint function on the hooks contract, only if the
+/// BEFORE_MINT_FLAG is set in the hooksParameters
+pub fn before_mint(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    to: &Addr,
+    liquidity_configs: &[LiquidityConfigurations],
+    amounts_received: Bytes32,
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & BEFORE_MINT != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::BeforeMint {
+                        sender: sender.to_string(),
+                        to: to.to_string(),
+                        liquidity_configs: liquidity_configs
+                            .iter()
+                            .map(|config| config.0)
+                            .collect(),
+                        amounts_received,
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Helper function to call the afterMint function on the hooks contract, only if the
+/// AFTER_MINT_FLAG is set in the hooksParameters
+pub fn after_mint(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    to: &Addr,
+    liquidity_configs: &[LiquidityConfigurations],
+    amounts_in: Bytes32,
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & AFTER_MINT != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::AfterMint {
+                        sender: sender.to_string(),
+                        to: to.to_string(),
+                        liquidity_configs: liquidity_configs
+                            .iter()
+                            .map(|config| config.0)
+                            .collect(),
+                        amounts_in,
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Helper function to call the beforeBurn function on the hooks contract, only if the
+/// BEFORE_BURN_FLAG is set in the hooksParameters
+pub fn before_burn(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    from: &Addr,
+    to: &Addr,
+    ids: &[u32],
+    amounts_to_burn: &[Uint256],
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & BEFORE_BURN != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::BeforeBurn {
+                        sender: sender.to_string(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        ids: ids.to_vec(),
+                        amounts_to_burn: amounts_to_burn.to_vec(),
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+/// Helper function to call the afterBurn function on the hooks contract, only if the
+/// AFTER_BURN_FLAG is set in the hooksParameters
+pub fn after_burn(
+    hooks_parameters: Option<HooksParameters>,
+    sender: &Addr,
+    from: &Addr,
+    to: &Addr,
+    ids: &[u32],
+    amounts_to_burn: &[Uint256],
+) -> StdResult<Option<WasmMsg>> {
+    match hooks_parameters {
+        Some(hooks_parameters) => {
+            if hooks_parameters.flags & AFTER_BURN != 0 {
+                Ok(Some(WasmMsg::Execute {
+                    contract_addr: hooks_parameters.address,
+                    code_hash: hooks_parameters.code_hash,
+                    msg: to_binary(&ExecuteMsg::AfterBurn {
+                        sender: sender.to_string(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                        ids: ids.to_vec(),
+                        amounts_to_burn: amounts_to_burn.to_vec(),
+                    })?,
+                    funds: vec![],
+                }))
+            } else {
+                Ok(None)
+            }
+        }
+        None => Ok(None),
+    }
+}
+
 /// Helper function to call the beforeTransferFrom function on the hooks contract, only if the
 /// BEFORE_TRANSFER_FLAG is set in the hooksParameters
 pub fn before_transfer_from(
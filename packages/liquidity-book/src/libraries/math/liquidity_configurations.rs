@@ -8,6 +8,7 @@ use super::{
     packed_u128_math::PackedUint128Math,
 };
 use crate::Bytes32;
+use cosmwasm_std::{Uint128, Uint64};
 use ethnum::U256;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -22,6 +23,14 @@ pub const PRECISION: u64 = 1_000_000_000_000_000_000; // 1e18
 pub enum LiquidityConfigurationsError {
     #[error("Liquidity Configurations Error: Distribution must be less than {PRECISION}")]
     InvalidConfig,
+    #[error("Liquidity Configurations Error: Invalid range [{id_min}, {id_max}] for active id {active_id}")]
+    InvalidRange {
+        id_min: u32,
+        id_max: u32,
+        active_id: u32,
+    },
+    #[error("Liquidity Configurations Error: Invalid shape")]
+    InvalidShape,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Copy, PartialEq, JsonSchema)]
@@ -114,6 +123,203 @@ impl LiquidityConfigurations {
     }
 }
 
+/// The shape of a liquidity position across its range of bins.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub enum LiquidityShape {
+    /// The same amount in every bin, also known as spot.
+    Uniform,
+    /// A normal curve centered on the active bin, with a standard deviation of `sigma` bins.
+    Curve { sigma: u32 },
+    /// Amounts growing linearly with the distance to the active bin.
+    BidAsk,
+}
+
+/// A liquidity distribution relative to the active bin, in the format used by the router.
+///
+/// Token X can only be added to bins at or above the active id, and token Y to bins at or below
+/// it, so a range that is entirely on one side of the active id gives a single-sided position.
+/// Each non-zero distribution sums to exactly `PRECISION`.
+///
+/// The fields map one to one to those of the router's `LiquidityParameters`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiquidityDistribution {
+    pub amount_x: Uint128,
+    pub amount_y: Uint128,
+    pub delta_ids: Vec<i64>,
+    pub distribution_x: Vec<Uint64>,
+    pub distribution_y: Vec<Uint64>,
+}
+
+impl LiquidityDistribution {
+    /// Builds the distribution of `amount_x` and `amount_y` over the bins `[id_min, id_max]`,
+    /// following `shape`.
+    ///
+    /// The active bin receives half of its weight in each token. The rounding residue of each
+    /// distribution is added to the bin with the largest weight for that token.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape` - The shape of the position
+    /// * `active_id` - The active id of the pair
+    /// * `id_min` - The lowest bin id of the range
+    /// * `id_max` - The highest bin id of the range
+    /// * `amount_x` - The amount of token X to add, none if zero
+    /// * `amount_y` - The amount of token Y to add, none if zero
+    pub fn from_shape(
+        shape: LiquidityShape,
+        active_id: u32,
+        id_min: u32,
+        id_max: u32,
+        amount_x: Uint128,
+        amount_y: Uint128,
+    ) -> Result<Self, LiquidityConfigurationsError> {
+        let (add_x, add_y) = (!amount_x.is_zero(), !amount_y.is_zero());
+
+        let invalid_range = LiquidityConfigurationsError::InvalidRange {
+            id_min,
+            id_max,
+            active_id,
+        };
+
+        if id_min > id_max || (add_x && id_max < active_id) || (add_y && id_min > active_id) {
+            return Err(invalid_range);
+        }
+
+        let mut delta_ids = Vec::with_capacity((id_max - id_min + 1) as usize);
+        let mut weights_x = Vec::with_capacity(delta_ids.capacity());
+        let mut weights_y = Vec::with_capacity(delta_ids.capacity());
+
+        for id in id_min..=id_max {
+            let weight = Self::_get_weight(shape, id.abs_diff(active_id))?;
+
+            let (weight_x, weight_y) = match id.cmp(&active_id) {
+                std::cmp::Ordering::Greater => (weight, 0),
+                std::cmp::Ordering::Equal => (weight / 2, weight / 2),
+                std::cmp::Ordering::Less => (0, weight),
+            };
+
+            delta_ids.push(id as i64 - active_id as i64);
+            weights_x.push(if add_x { weight_x } else { 0 });
+            weights_y.push(if add_y { weight_y } else { 0 });
+        }
+
+        Ok(Self {
+            amount_x,
+            amount_y,
+            delta_ids,
+            distribution_x: Self::_normalize(&weights_x, add_x)?,
+            distribution_y: Self::_normalize(&weights_y, add_y)?,
+        })
+    }
+
+    /// Returns the liquidity configurations of the distribution for the given active id, as
+    /// expected by the pair's `mint`.
+    pub fn to_liquidity_configurations(&self, active_id: u32) -> Vec<LiquidityConfigurations> {
+        self.delta_ids
+            .iter()
+            .zip(self.distribution_x.iter().zip(self.distribution_y.iter()))
+            .map(|(delta_id, (distribution_x, distribution_y))| {
+                LiquidityConfigurations::encode_params(
+                    distribution_x.u64(),
+                    distribution_y.u64(),
+                    (active_id as i64 + delta_id) as u32,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the weight of a bin at `distance` bins from the active bin, with 18 decimals.
+    fn _get_weight(
+        shape: LiquidityShape,
+        distance: u32,
+    ) -> Result<u128, LiquidityConfigurationsError> {
+        let precision = PRECISION as u128;
+
+        match shape {
+            LiquidityShape::Uniform => Ok(precision),
+            LiquidityShape::BidAsk => Ok((distance as u128 + 1) * precision),
+            LiquidityShape::Curve { sigma: 0 } => Err(LiquidityConfigurationsError::InvalidShape),
+            LiquidityShape::Curve { sigma } => {
+                let distance = distance as u128;
+                let sigma = sigma as u128;
+
+                // exp(-d^2 / (2 * sigma^2))
+                Ok(Self::_exp_neg(
+                    distance * distance * precision / (2 * sigma * sigma),
+                ))
+            }
+        }
+    }
+
+    /// Returns `e^(-x)` for `x` with 18 decimals, with 18 decimals.
+    fn _exp_neg(x: u128) -> u128 {
+        const E: u128 = 2_718_281_828_459_045_235; // e with 18 decimals
+
+        let precision = U256::from(PRECISION);
+        let (integer, fraction) = (x / PRECISION as u128, x % PRECISION as u128);
+
+        // e^-42 is below 1e-18
+        if integer >= 42 {
+            return 0;
+        }
+
+        // e^fraction, using its Taylor series
+        let mut term = precision;
+        let mut exp = precision;
+
+        for k in 1..=32u128 {
+            term = term * U256::from(fraction) / (U256::from(k) * precision);
+            if term == U256::ZERO {
+                break;
+            }
+            exp += term;
+        }
+
+        for _ in 0..integer {
+            exp = exp * U256::from(E) / precision;
+        }
+
+        (precision * precision / exp).as_u128()
+    }
+
+    /// Scales the weights so that they sum to exactly `PRECISION`, or returns zeros if the
+    /// token is not added.
+    fn _normalize(
+        weights: &[u128],
+        is_added: bool,
+    ) -> Result<Vec<Uint64>, LiquidityConfigurationsError> {
+        if !is_added {
+            return Ok(vec![Uint64::zero(); weights.len()]);
+        }
+
+        let total_weight = weights
+            .iter()
+            .fold(U256::ZERO, |total, weight| total + U256::from(*weight));
+
+        if total_weight == U256::ZERO {
+            return Err(LiquidityConfigurationsError::InvalidShape);
+        }
+
+        let mut distribution: Vec<u64> = weights
+            .iter()
+            .map(|weight| (U256::from(*weight) * U256::from(PRECISION) / total_weight).as_u64())
+            .collect();
+
+        let residue = PRECISION - distribution.iter().sum::<u64>();
+
+        // The first bin with the largest weight absorbs the rounding residue.
+        let mut largest = 0;
+        for (i, weight) in weights.iter().enumerate() {
+            if *weight > weights[largest] {
+                largest = i;
+            }
+        }
+        distribution[largest] += residue;
+
+        Ok(distribution.into_iter().map(Uint64::new).collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +465,121 @@ mod tests {
         let cloned_config = config.clone();
         assert_eq!(config, cloned_config);
     }
+
+    #[test]
+    fn test_uniform_distribution_sums_to_precision() {
+        let distribution = LiquidityDistribution::from_shape(
+            LiquidityShape::Uniform,
+            100,
+            97,
+            103,
+            Uint128::new(1000),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        assert_eq!(distribution.delta_ids, vec![-3, -2, -1, 0, 1, 2, 3]);
+        assert_eq!(
+            distribution.distribution_x.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert_eq!(
+            distribution.distribution_y.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert_eq!(distribution.distribution_x[..3], [Uint64::zero(); 3]);
+        assert_eq!(distribution.distribution_y[4..], [Uint64::zero(); 3]);
+        assert_eq!(
+            distribution.distribution_x[5],
+            distribution.distribution_x[6]
+        );
+        assert!(distribution.distribution_x[3] < distribution.distribution_x[5]);
+    }
+
+    #[test]
+    fn test_curve_distribution_peaks_at_active_id() {
+        let distribution = LiquidityDistribution::from_shape(
+            LiquidityShape::Curve { sigma: 2 },
+            100,
+            95,
+            105,
+            Uint128::new(1000),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            distribution.distribution_x.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert_eq!(
+            distribution.distribution_y.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert!(distribution.distribution_x[6] > distribution.distribution_x[7]);
+        assert!(distribution.distribution_y[4] > distribution.distribution_y[3]);
+    }
+
+    #[test]
+    fn test_bid_ask_distribution_grows_with_distance() {
+        let distribution = LiquidityDistribution::from_shape(
+            LiquidityShape::BidAsk,
+            100,
+            98,
+            102,
+            Uint128::new(1000),
+            Uint128::new(1000),
+        )
+        .unwrap();
+
+        assert_eq!(
+            distribution.distribution_x.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert!(distribution.distribution_x[4] > distribution.distribution_x[3]);
+        assert!(distribution.distribution_y[0] > distribution.distribution_y[1]);
+    }
+
+    #[test]
+    fn test_single_sided_distribution() {
+        let distribution = LiquidityDistribution::from_shape(
+            LiquidityShape::Uniform,
+            100,
+            101,
+            103,
+            Uint128::new(1000),
+            Uint128::zero(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            distribution.distribution_x.iter().sum::<Uint64>(),
+            Uint64::new(PRECISION)
+        );
+        assert_eq!(distribution.amount_x, Uint128::new(1000));
+        assert_eq!(distribution.distribution_y, vec![Uint64::zero(); 3]);
+
+        let configs = distribution.to_liquidity_configurations(100);
+        assert_eq!(
+            LiquidityConfigurations::decode_params(configs[0].0),
+            (distribution.distribution_x[0].u64(), 0, 101)
+        );
+
+        let result = LiquidityDistribution::from_shape(
+            LiquidityShape::Uniform,
+            100,
+            101,
+            103,
+            Uint128::zero(),
+            Uint128::new(1000),
+        );
+        assert_eq!(
+            result,
+            Err(LiquidityConfigurationsError::InvalidRange {
+                id_min: 101,
+                id_max: 103,
+                active_id: 100
+            })
+        );
+    }
 }
@@ -0,0 +1,35 @@
+//! Fixtures shared by the unit tests of the contracts, enabled by the `testing` feature.
+
+use crate::{core::TokenType, interfaces::lb_pair::LbPair};
+use cosmwasm_std::{Addr, Binary, ContractInfo};
+
+/// The id of the bin whose price is 1.
+pub const ACTIVE_ID: u32 = 1 << 23;
+pub const BIN_STEP: u16 = 20;
+
+/// Returns a SNIP-20 token deployed at `name`, with the code hash `{name}_hash`.
+pub fn mock_token(name: &str) -> TokenType {
+    TokenType::CustomToken {
+        contract_addr: Addr::unchecked(name),
+        token_code_hash: format!("{name}_hash"),
+    }
+}
+
+/// Returns the pair of `token_x` and `token_y`, with a bin step of `BIN_STEP`, deployed at
+/// `lb_pair`.
+pub fn mock_lb_pair() -> LbPair {
+    LbPair {
+        token_x: mock_token("token_x"),
+        token_y: mock_token("token_y"),
+        bin_step: BIN_STEP,
+        contract: ContractInfo {
+            address: Addr::unchecked("lb_pair"),
+            code_hash: "lb_pair_hash".to_string(),
+        },
+    }
+}
+
+/// Returns the response of a SNIP-20 balance query.
+pub fn mock_snip20_balance(amount: u128) -> Binary {
+    Binary::from(format!(r#"{{"balance":{{"amount":"{amount}"}}}}"#).into_bytes())
+}
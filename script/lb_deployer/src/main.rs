@@ -14,7 +14,7 @@ use crate::{
     utils::{check_gas, code_hash_by_code_id, execute, instantiate, sha256, store_code},
 };
 use color_eyre::{owo_colors::OwoColorize, Result};
-use cosmwasm_std::{to_binary, Addr, Binary, ContractInfo, Uint128, Uint64};
+use cosmwasm_std::{to_binary, Addr, ContractInfo, Uint128, Uint64};
 use ethnum::U256;
 use liquidity_book::{
     core::{RawContract, TokenType},
@@ -31,7 +31,6 @@ use secretrs::{
     utils::EnigmaUtils,
 };
 use serde::{Deserialize, Serialize};
-use shade_protocol::Contract;
 use tonic::transport::Certificate;
 use tonic::transport::ClientTlsConfig;
 
@@ -84,8 +83,6 @@ async fn main() -> Result<()> {
     let wallet_address = secretrs.wallet.addr();
 
     // Store Code
-    let admin = Path::new("./script/lb_deployer/code/admin.wasm.gz");
-    let query_auth = Path::new("./script/lb_deployer/code/query_auth.wasm.gz");
     let query_router = Path::new("./script/lb_deployer/code/query_router.wasm");
     let snip20 = Path::new("./script/lb_deployer/code/snip20.wasm.gz");
     let snip25 = Path::new("./script/lb_deployer/code/snip25-amber.wasm.gz");
@@ -95,8 +92,6 @@ async fn main() -> Result<()> {
     let lb_router = Path::new("./code/lb_router.wasm.gz");
     let lb_quoter = Path::new("./code/lb_quoter.wasm.gz");
 
-    let admin_code_id = store_code(admin, 1_000_000).await?;
-    let query_auth_code_id = store_code(query_auth, 1_400_000).await?;
     let query_router_code_id = store_code(query_router, 1_700_000).await?;
     let snip20_code_id = store_code(snip20, 1_200_000).await?;
     let snip25_code_id = store_code(snip25, 2_900_000).await?;
@@ -109,8 +104,6 @@ async fn main() -> Result<()> {
     info!("Gas used to store codes: {}", check_gas());
 
     // TODO: hash the code directly
-    let admin_code_hash = code_hash_by_code_id(admin_code_id).await?;
-    let query_auth_code_hash = code_hash_by_code_id(query_auth_code_id).await?;
     let query_router_code_hash = code_hash_by_code_id(query_router_code_id).await?;
     let snip20_code_hash = code_hash_by_code_id(snip20_code_id).await?;
     let snip25_code_hash = code_hash_by_code_id(snip25_code_id).await?;
@@ -122,28 +115,6 @@ async fn main() -> Result<()> {
 
     // Instantiate
 
-    info!("Instantiating admin...",);
-    let admin_init_msg = shade_protocol::contract_interfaces::admin::InstantiateMsg {
-        super_admin: Some(wallet_address.to_string()),
-    };
-    let admin = instantiate(admin_code_id, &admin_code_hash, &admin_init_msg, 100_000).await?;
-
-    info!("Instantiating query_auth...",);
-    let query_auth_init_msg = shade_protocol::contract_interfaces::query_auth::InstantiateMsg {
-        admin_auth: Contract {
-            address: Addr::unchecked(admin.address.clone()),
-            code_hash: admin.code_hash.clone(),
-        },
-        prng_seed: Binary([1u8; 32].to_vec()),
-    };
-    let query_auth = instantiate(
-        query_auth_code_id,
-        &query_auth_code_hash,
-        &query_auth_init_msg,
-        100_000,
-    )
-    .await?;
-
     #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
     pub struct EmptyInstantiateMsg {}
 
@@ -159,15 +130,6 @@ async fn main() -> Result<()> {
 
     info!("Instantiating lb_factory...",);
     let lb_factory_init_msg = lb_factory::InstantiateMsg {
-        // TODO is it meant to be admin_auth or admin?
-        admin_auth: RawContract {
-            address: admin.address.to_string(),
-            code_hash: admin.code_hash.to_string(),
-        },
-        query_auth: RawContract {
-            address: query_auth.address.to_string(),
-            code_hash: query_auth.code_hash.to_string(),
-        },
         owner: Some(wallet_address.clone()),
         fee_recipient: wallet_address.clone(),
     };
@@ -645,16 +607,6 @@ async fn main() -> Result<()> {
     };
 
     let deployment = DeployedContracts {
-        admin_auth: DeployedContractInfo {
-            address: admin.address,
-            code_hash: admin.code_hash,
-            code_id: admin_code_id,
-        },
-        query_auth: DeployedContractInfo {
-            address: query_auth.address,
-            code_hash: query_auth.code_hash,
-            code_id: query_auth_code_id,
-        },
         snip20: DeployedContractInfo {
             address: snip20.address,
             code_hash: snip20.code_hash,
@@ -770,8 +722,6 @@ impl Default for DeployedContractInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeployedContracts {
-    pub admin_auth: DeployedContractInfo,
-    pub query_auth: DeployedContractInfo,
     pub snip20: DeployedContractInfo,
     pub snip25: DeployedContractInfo,
     pub lb_factory: DeployedContractInfo,
@@ -784,8 +734,6 @@ pub struct DeployedContracts {
 impl DeployedContracts {
     pub fn new() -> Self {
         DeployedContracts {
-            admin_auth: DeployedContractInfo::default(),
-            query_auth: DeployedContractInfo::default(),
             snip20: DeployedContractInfo::default(),
             snip25: DeployedContractInfo::default(),
             lb_factory: DeployedContractInfo::default(),
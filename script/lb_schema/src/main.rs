@@ -19,6 +19,7 @@ fn main() {
         instantiate: lb_factory::InstantiateMsg,
         execute: lb_factory::ExecuteMsg,
         query: lb_factory::QueryMsg,
+        migrate: lb_factory::MigrateMsg,
     }
     .render();
 
@@ -50,6 +51,7 @@ fn main() {
         instantiate: lb_pair::InstantiateMsg,
         execute: lb_pair::ExecuteMsg,
         query: lb_pair::QueryMsg,
+        migrate: lb_pair::MigrateMsg,
     }
     .render();
 
@@ -81,6 +83,7 @@ fn main() {
         instantiate: lb_quoter::InstantiateMsg,
         execute: lb_quoter::ExecuteMsg,
         query: lb_quoter::QueryMsg,
+        migrate: lb_quoter::MigrateMsg,
     }
     .render();
 
@@ -112,6 +115,7 @@ fn main() {
         instantiate: lb_router::InstantiateMsg,
         execute: lb_router::ExecuteMsg,
         query: lb_router::QueryMsg,
+        migrate: lb_router::MigrateMsg,
     }
     .render();
 
@@ -165,4 +169,36 @@ fn main() {
         write(&path, json + "\n").unwrap();
         println!("Exported {}", path.to_str().unwrap());
     }
+
+    // ---
+
+    let mut out_dir = env::current_dir().unwrap();
+
+    out_dir.push("schema/lb_limit_order");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    let limit_order_api = generate_api! {
+        instantiate: lb_limit_order::InstantiateMsg,
+        execute: lb_limit_order::ExecuteMsg,
+        query: lb_limit_order::QueryMsg,
+        migrate: lb_limit_order::MigrateMsg,
+    }
+    .render();
+
+    let path = out_dir.join(concat!("lb_limit_order", ".json"));
+
+    let json = limit_order_api.to_string().unwrap();
+    write(&path, json + "\n").unwrap();
+    println!("Exported the full API as {}", path.to_str().unwrap());
+
+    let raw_dir = out_dir.join("raw");
+    create_dir_all(&raw_dir).unwrap();
+
+    for (filename, json) in limit_order_api.to_schema_files().unwrap() {
+        let path = raw_dir.join(filename);
+
+        write(&path, json + "\n").unwrap();
+        println!("Exported {}", path.to_str().unwrap());
+    }
 }
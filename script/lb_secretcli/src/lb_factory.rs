@@ -3,8 +3,11 @@ mod example_data;
 use cosmwasm_std::{Addr, ContractInfo};
 use example_data::{ExampleData, VariousAddr, ACTIVE_ID, BIN_STEP};
 use liquidity_book::{
-    core::{RawContract, TokenType},
-    interfaces::{lb_factory::*, lb_pair::LbPair},
+    core::{access_control::LB_HOOKS_MANAGER_ROLE, TokenType},
+    interfaces::{
+        lb_factory::*,
+        lb_pair::{ContractStatus, LbPair},
+    },
 };
 use std::{
     env,
@@ -70,10 +73,8 @@ pub fn main() -> io::Result<()> {
     // -- Instantiate Message
 
     let instantiate_msg = InstantiateMsg {
-        admin_auth: RawContract::example(),
         owner: Some(Addr::owner()),
         fee_recipient: Addr::recipient(),
-        query_auth: RawContract::example(),
     };
 
     writeln!(file, "## Instantiate Message\n")?;
@@ -153,6 +154,46 @@ pub fn main() -> io::Result<()> {
         },
     };
 
+    let set_status_on_all_pairs = ExecuteMsg::SetStatusOnAllPairs {
+        status: ContractStatus::FreezeAll,
+        start_after: None,
+        limit: Some(10),
+    };
+
+    let add_hooks_to_whitelist = ExecuteMsg::AddHooksToWhitelist {
+        hooks_implementation: Implementation::example(),
+    };
+
+    let remove_hooks_from_whitelist = ExecuteMsg::RemoveHooksFromWhitelist { code_id: 1 };
+
+    let grant_role = ExecuteMsg::GrantRole {
+        role: LB_HOOKS_MANAGER_ROLE.to_string(),
+        account: Addr::sender().to_string(),
+    };
+
+    let revoke_role = ExecuteMsg::RevokeRole {
+        role: LB_HOOKS_MANAGER_ROLE.to_string(),
+        account: Addr::sender().to_string(),
+    };
+
+    let renounce_role = ExecuteMsg::RenounceRole {
+        role: LB_HOOKS_MANAGER_ROLE.to_string(),
+    };
+
+    let set_timelock_delay = ExecuteMsg::SetTimelockDelay { delay: 86_400 };
+
+    let execute_timelocked_operation = ExecuteMsg::ExecuteTimelockedOperation { id: 0 };
+
+    let cancel_timelocked_operation = ExecuteMsg::CancelTimelockedOperation { id: 0 };
+
+    let transfer_ownership = ExecuteMsg::TransferOwnership {
+        new_owner: Addr::recipient().to_string(),
+    };
+
+    let accept_ownership = ExecuteMsg::AcceptOwnership {};
+
+    let renounce_ownership = ExecuteMsg::RenounceOwnership {};
+
     writeln!(file, "## Execute Messages\n")?;
     print_execute_messages!(
         file,
@@ -166,7 +207,19 @@ pub fn main() -> io::Result<()> {
         set_fee_recipient,
         add_quote_asset,
         remove_quote_asset,
-        force_decay
+        force_decay,
+        set_status_on_all_pairs,
+        add_hooks_to_whitelist,
+        remove_hooks_from_whitelist,
+        grant_role,
+        revoke_role,
+        renounce_role,
+        set_timelock_delay,
+        execute_timelocked_operation,
+        cancel_timelocked_operation,
+        transfer_ownership,
+        accept_ownership,
+        renounce_ownership
     );
 
     // -- Query Messages
@@ -194,6 +247,22 @@ pub fn main() -> io::Result<()> {
         token_x: TokenType::example(),
         token_y: TokenType::example(),
     };
+    let get_hooks_whitelist = QueryMsg::GetHooksWhitelist {};
+    let get_pairs_status = QueryMsg::GetPairsStatus {
+        start_after: None,
+        limit: Some(10),
+    };
+    let get_timelock_delay = QueryMsg::GetTimelockDelay {};
+    let get_pending_operations = QueryMsg::GetPendingOperations {
+        start_after: None,
+        limit: Some(10),
+    };
+    let get_owner = QueryMsg::GetOwner {};
+    let get_pending_owner = QueryMsg::GetPendingOwner {};
+    let has_role = QueryMsg::HasRole {
+        role: LB_HOOKS_MANAGER_ROLE.to_string(),
+        account: Addr::sender().to_string(),
+    };
 
     // responses
 
@@ -255,6 +324,45 @@ pub fn main() -> io::Result<()> {
         lb_pairs_available: vec![LbPairInformation::example(), LbPairInformation::example()],
     };
 
+    let get_hooks_whitelist_response = HooksWhitelistResponse {
+        hooks_whitelist: vec![Implementation::example()],
+    };
+
+    let get_pairs_status_response = PairsStatusResponse {
+        status: ContractStatus::FreezeAll,
+        lb_pairs: vec![LbPairStatus {
+            lb_pair: LbPair {
+                token_x: TokenType::example(),
+                token_y: TokenType::example(),
+                bin_step: 100,
+                contract: ContractInfo::example(),
+            },
+            contract_status: Some(ContractStatus::FreezeAll),
+        }],
+    };
+
+    let get_timelock_delay_response = TimelockDelayResponse { delay: 86_400 };
+
+    let get_pending_operations_response = PendingOperationsResponse {
+        pending_operations: vec![PendingOperation {
+            id: 0,
+            operation: TimelockedOperation::SetLbPairImplementation {
+                implementation: Implementation::example(),
+            },
+            eta: 1739317404,
+        }],
+    };
+
+    let get_owner_response = OwnerResponse {
+        owner: Addr::owner(),
+    };
+
+    let get_pending_owner_response = PendingOwnerResponse {
+        pending_owner: Some(Addr::recipient()),
+    };
+
+    let has_role_response = HasRoleResponse { has_role: true };
+
     writeln!(file, "## Query Messages with responses\n")?;
     print_query_messages_with_responses!(
         file,
@@ -281,6 +389,13 @@ pub fn main() -> io::Result<()> {
         (get_all_bin_steps, get_all_bin_steps_response),
         (get_open_bin_steps, get_open_bin_steps_response),
         (get_all_lb_pairs, get_all_lb_pairs_response),
+        (get_hooks_whitelist, get_hooks_whitelist_response),
+        (get_pairs_status, get_pairs_status_response),
+        (get_timelock_delay, get_timelock_delay_response),
+        (get_pending_operations, get_pending_operations_response),
+        (get_owner, get_owner_response),
+        (get_pending_owner, get_pending_owner_response),
+        (has_role, has_role_response),
     );
 
     println!("Created {}", file_path.display());
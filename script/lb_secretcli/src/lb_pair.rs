@@ -4,12 +4,15 @@ use cosmwasm_std::{Addr, ContractInfo, Uint128, Uint256};
 use ethnum::U256;
 use example_data::*;
 use liquidity_book::{
-    core::{RawContract, TokenType},
+    core::TokenType,
     interfaces::{
         lb_factory::{Implementation, StaticFeeParameters},
         lb_pair::*,
     },
-    libraries::{math::uint256_to_u256::ConvertU256, pair_parameter_helper::PairParameters},
+    libraries::{
+        math::uint256_to_u256::ConvertU256, pair_parameter_helper::PairParameters,
+        LiquidityConfigurations,
+    },
 };
 use std::{
     env,
@@ -90,7 +93,6 @@ pub fn main() -> io::Result<()> {
         .unwrap();
 
     let instantiate_msg = InstantiateMsg {
-        admin_auth: RawContract::example(),
         factory: ContractInfo::example(),
         token_x: TokenType::example(),
         token_y: TokenType::example(),
@@ -108,7 +110,6 @@ pub fn main() -> io::Result<()> {
         lb_token_implementation: Implementation::empty(),
         viewing_key: String::from("viewing_key"),
         entropy: String::from("entropy"),
-        query_auth: RawContract::example(),
     };
 
     writeln!(file, "## Instantiate Message\n")?;
@@ -119,13 +120,27 @@ pub fn main() -> io::Result<()> {
     let swap_tokens = ExecuteMsg::Swap {
         swap_for_y: true,
         to: Addr::recipient().to_string(),
+        id_limit: None,
     };
 
     let swap_tokens_invoke = InvokeMsg::Swap {
         swap_for_y: true,
         to: Addr::recipient().to_string(),
+        id_limit: None,
     };
 
+    let mint_invoke = InvokeMsg::Mint {
+        to: Addr::recipient().to_string(),
+        liquidity_configs: vec![LiquidityConfigurations::encode_params(
+            500_000_000_000_000_000,
+            500_000_000_000_000_000,
+            8388608,
+        )],
+        refund_to: Addr::sender().to_string(),
+    };
+
+    let withdraw_pending_mint = ExecuteMsg::WithdrawPendingMint {};
+
     let collect_protocol_fees = ExecuteMsg::CollectProtocolFees {};
 
     let set_static_fee_parameters = ExecuteMsg::SetStaticFeeParameters {
@@ -148,6 +163,8 @@ pub fn main() -> io::Result<()> {
         file,
         swap_tokens,
         swap_tokens_invoke,
+        mint_invoke,
+        withdraw_pending_mint,
         collect_protocol_fees,
         set_static_fee_parameters,
         force_decay,
@@ -188,10 +205,16 @@ pub fn main() -> io::Result<()> {
     let get_swap_in = QueryMsg::GetSwapIn {
         amount_out: Uint128::from(100_000u128),
         swap_for_y: true,
+        id_limit: None,
+        at_timestamp: None,
+        volatility_accumulator: None,
     };
     let get_swap_out = QueryMsg::GetSwapOut {
         amount_in: Uint128::from(100_000u128),
         swap_for_y: true,
+        id_limit: None,
+        at_timestamp: None,
+        volatility_accumulator: None,
     };
 
     // not in joe-v2
@@ -302,12 +325,14 @@ pub fn main() -> io::Result<()> {
         amount_in: Uint128::from(1000u128),
         amount_out_left: Uint128::from(10u128),
         fee: Uint128::from(10u128),
+        bin_fees: vec![],
     };
 
     let get_swap_out_response = SwapOutResponse {
         amount_in_left: Uint128::from(1000u128),
         amount_out: Uint128::from(10u128),
         fee: Uint128::from(100u128),
+        bin_fees: vec![],
     };
 
     let get_lb_token_supply_response = LbTokenSupplyResponse {
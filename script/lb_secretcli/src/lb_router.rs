@@ -95,6 +95,11 @@ pub fn main() -> io::Result<()> {
         liquidity_parameters: LiquidityParameters::example(),
     };
 
+    let create_lb_pair_and_add_liquidity = ExecuteMsg::CreateLbPairAndAddLiquidity {
+        active_id: ACTIVE_ID,
+        liquidity_parameters: LiquidityParameters::example(),
+    };
+
     let swap_exact_tokens_for_tokens = ExecuteMsg::SwapExactTokensForTokens {
         amount_in: Uint128::new(1_000_000),
         amount_out_min: Uint128::new(950_000),
@@ -108,6 +113,7 @@ pub fn main() -> io::Result<()> {
         file,
         create_lb_pair,
         add_liquidity,
+        create_lb_pair_and_add_liquidity,
         swap_exact_tokens_for_tokens
     );
 